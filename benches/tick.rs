@@ -0,0 +1,54 @@
+//! Baseline timings for `tick`, run with `cargo bench`.
+//!
+//! Every iteration ticks a freshly built grid, so the soups are timed while
+//! they're still soups rather than after they've burned down to ash. Each
+//! grid is ticked once while it's built, so the timed tick tracks changed
+//! regions like it would in a running simulation.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use wasm_game_of_life::Engine;
+
+fn soup(size: u32) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_width(size);
+    engine.set_height(size);
+    engine.set_history_limit(0);
+    engine.randomize(42, 0.5);
+    engine.tick();
+    engine
+}
+
+fn glider(size: u32) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_width(size);
+    engine.set_height(size);
+    engine.set_history_limit(0);
+    engine.insert_pattern("glider", size / 2, size / 2).unwrap();
+    engine.tick();
+    engine
+}
+
+/// Ticks the engine once; returning it keeps its drop out of the timing.
+fn tick(mut engine: Engine) -> Engine {
+    engine.tick();
+    engine
+}
+
+fn bench_dense(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick/soup");
+    for &size in [64, 256, 1024].iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter_batched(|| soup(size), tick, BatchSize::LargeInput);
+        });
+    }
+    group.finish();
+}
+
+fn bench_sparse(c: &mut Criterion) {
+    c.bench_function("tick/glider_256", |b| {
+        b.iter_batched(|| glider(256), tick, BatchSize::LargeInput);
+    });
+}
+
+criterion_group!(benches, bench_dense, bench_sparse);
+criterion_main!(benches);
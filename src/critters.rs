@@ -0,0 +1,178 @@
+//! The Critters block cellular automaton.
+//!
+//! Critters uses the Margolus neighborhood: the grid is split into 2x2
+//! blocks, and the split shifts by one cell diagonally on alternate
+//! generations. Each block is updated on its own:
+//!
+//! - with exactly two live cells it is left alone,
+//! - with zero, one or four it is complemented,
+//! - with three it is complemented and rotated 180 degrees.
+//!
+//! Every block update is a bijection, so the automaton is exactly
+//! reversible and `tick_back` undoes `tick` without storing any history.
+
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+use crate::engine::check_dimensions;
+use crate::rng::Rng;
+use crate::{to_js, Cell};
+
+#[wasm_bindgen]
+pub struct CrittersUniverse {
+    width: u32,
+    height: u32,
+    cells: Vec<Cell>,
+    generation: u32,
+}
+
+impl CrittersUniverse {
+    pub fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+    pub fn get_cells(&self) -> &[Cell] {
+        &self.cells
+    }
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+        for (row, col) in cells.iter().cloned() {
+            if row < self.height && col < self.width {
+                let idx = self.get_index(row, col);
+                self.cells[idx] = Cell::Alive;
+            }
+        }
+    }
+    /// Applies `update` to every Margolus block of the partition whose
+    /// blocks start at `(offset, offset)`.
+    fn update_blocks(&mut self, offset: u32, update: fn(&mut [Cell; 4])) {
+        for top in (offset..self.height + offset).step_by(2) {
+            for left in (offset..self.width + offset).step_by(2) {
+                // Block cells in reading order: NW, NE, SW, SE.
+                let indices = [
+                    self.get_index(top % self.height, left % self.width),
+                    self.get_index(top % self.height, (left + 1) % self.width),
+                    self.get_index((top + 1) % self.height, left % self.width),
+                    self.get_index((top + 1) % self.height, (left + 1) % self.width),
+                ];
+                let mut block = [
+                    self.cells[indices[0]],
+                    self.cells[indices[1]],
+                    self.cells[indices[2]],
+                    self.cells[indices[3]],
+                ];
+                update(&mut block);
+                for (&idx, &cell) in indices.iter().zip(block.iter()) {
+                    self.cells[idx] = cell;
+                }
+            }
+        }
+    }
+}
+
+fn live_count(block: &[Cell; 4]) -> usize {
+    block.iter().filter(|&&cell| cell == Cell::Alive).count()
+}
+
+fn complement(block: &mut [Cell; 4]) {
+    block.iter_mut().for_each(|cell| *cell = cell.toggle());
+}
+
+fn forward(block: &mut [Cell; 4]) {
+    match live_count(block) {
+        2 => {}
+        3 => {
+            complement(block);
+            block.reverse();
+        }
+        _ => complement(block),
+    }
+}
+
+/// The inverse of `forward`: blocks it complemented now hold four, three or
+/// zero live cells, and the rotated three-cell case now holds one.
+fn backward(block: &mut [Cell; 4]) {
+    match live_count(block) {
+        2 => {}
+        1 => {
+            complement(block);
+            block.reverse();
+        }
+        _ => complement(block),
+    }
+}
+
+#[wasm_bindgen]
+impl CrittersUniverse {
+    /// Creates an empty universe. Both dimensions must be even and non-zero
+    /// so the grid splits evenly into 2x2 blocks, and the grid may not
+    /// exceed the `Universe::enforce_max_dimensions` limit.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> Result<CrittersUniverse, JsValue> {
+        if width == 0 || height == 0 || width % 2 == 1 || height % 2 == 1 {
+            return Err(JsValue::from_str(
+                "Critters dimensions must be even and non-zero",
+            ));
+        }
+        check_dimensions(width, height).map_err(to_js)?;
+        Ok(CrittersUniverse {
+            width,
+            height,
+            cells: vec![Cell::Dead; (width * height) as usize],
+            generation: 0,
+        })
+    }
+    pub fn randomize(&mut self, seed: u64, density: f64) {
+        let mut rng = Rng::new(seed);
+        for cell in self.cells.iter_mut() {
+            *cell = if rng.next_f64() < density {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+    }
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        if row < self.height && column < self.width {
+            let idx = self.get_index(row, column);
+            self.cells[idx] = self.cells[idx].toggle();
+        }
+    }
+    pub fn tick(&mut self) {
+        self.update_blocks(self.generation % 2, forward);
+        self.generation = self.generation.wrapping_add(1);
+    }
+    /// Steps one generation back in time, exactly undoing the last `tick`.
+    /// Going back from generation 0 is allowed; the counter wraps.
+    pub fn tick_back(&mut self) {
+        self.generation = self.generation.wrapping_sub(1);
+        self.update_blocks(self.generation % 2, backward);
+    }
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    pub fn cells(&self) -> *const Cell {
+        self.cells.as_ptr()
+    }
+}
+
+impl fmt::Display for CrittersUniverse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.cells.as_slice().chunks(self.width as usize) {
+            for &cell in line {
+                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
+                write!(f, "{}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
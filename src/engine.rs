@@ -0,0 +1,2705 @@
+//! The simulation itself, free of any JS bindings so that it builds and
+//! tests on native targets. `Universe` in the crate root wraps an `Engine`
+//! for JS, delegating each method to the one of the same name here.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::isotropic::{self, IsotropicRule};
+use crate::rule::Rule;
+use crate::utils::{Stopwatch, Timer};
+use crate::{font, formats, patterns, rng, save, Cell};
+
+/// Mirror axis for the symmetry helpers. `Horizontal` flips left to right,
+/// `Vertical` flips top to bottom.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl Axis {
+    fn parse(name: &str) -> Result<Axis, String> {
+        match name {
+            "horizontal" => Ok(Axis::Horizontal),
+            "vertical" => Ok(Axis::Vertical),
+            "both" => Ok(Axis::Both),
+            _ => Err(format!("unknown axis `{}`", name)),
+        }
+    }
+}
+
+/// What lies beyond the edge of the grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Boundary {
+    /// The grid wraps around, so opposite edges are neighbors.
+    Toroidal,
+    /// Everything outside the grid is permanently dead.
+    Dead,
+}
+
+impl Boundary {
+    pub(crate) fn parse(name: &str) -> Result<Boundary, String> {
+        match name {
+            "toroidal" => Ok(Boundary::Toroidal),
+            "dead" => Ok(Boundary::Dead),
+            _ => Err(format!("unknown boundary `{}`", name)),
+        }
+    }
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Boundary::Toroidal => "toroidal",
+            Boundary::Dead => "dead",
+        }
+    }
+}
+
+/// Number of earlier states `step_back` can return to by default.
+const DEFAULT_HISTORY_LIMIT: usize = 32;
+
+/// Number of generations whose population is remembered.
+const POPULATION_HISTORY_LIMIT: usize = 1024;
+
+/// Most generations `tick_generations_into_history` keeps.
+const RECORDING_LIMIT: usize = 256;
+
+/// A saved state for `step_back` or a recorded generation.
+struct Snapshot {
+    cells: Vec<Cell>,
+    generation: u32,
+}
+
+/// A rectangle of cells, covering rows `top..bottom` and columns
+/// `left..right`.
+#[derive(Clone, Copy)]
+struct Region {
+    top: u32,
+    left: u32,
+    bottom: u32,
+    right: u32,
+}
+
+/// Neighbor weights for `tick_weighted` that reproduce plain neighbor
+/// counting: every Moore neighbor counts once and the cell itself not at all.
+const MOORE_KERNEL: [i32; 9] = [1, 1, 1, 1, 0, 1, 1, 1, 1];
+
+/// Chance of each cell starting alive in the soups of `soup_search`.
+const SOUP_DENSITY: f64 = 0.5;
+
+/// Longest row of cells `to_lif_105` writes; wider patterns are split into
+/// side-by-side blocks.
+const LIFE_105_LINE_WIDTH: usize = 80;
+
+/// Catalog pattern and top-left `(row, column)` of each oscillator placed by
+/// `prepopulate_common_oscillators`.
+const OSCILLATOR_SCENE: &[(&str, u32, u32)] = &[
+    ("pulsar", 3, 3),
+    ("pentadecathlon", 6, 25),
+    ("toad", 24, 6),
+    ("beacon", 22, 28),
+];
+
+/// Side length of the square soups `probe_rule_stability` runs.
+const PROBE_SIZE: u32 = 32;
+
+/// Ticks a `probe_rule_stability` soup gets to settle.
+const PROBE_STEPS: u32 = 100;
+
+/// A grid of cells. Either dimension may be zero, in which case the universe
+/// is empty: `tick` does nothing, `render` returns an empty string and
+/// coordinate-based edits are ignored.
+pub struct Engine {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) cells: Vec<Cell>,
+    pub(crate) generation: u32,
+    pub(crate) rule: Rule,
+    activity: Vec<u32>,
+    ticks_per_second: f64,
+    pending_ms: f64,
+    kernel: [i32; 9],
+    pub(crate) boundary: Boundary,
+    /// Rows and columns a toroidal wrap shifts by; see `set_torus_offset`.
+    pub(crate) torus_offset: (i32, i32),
+    escaped: u32,
+    escaped_per_edge: [u32; 4],
+    history: VecDeque<Snapshot>,
+    history_limit: usize,
+    ages: Option<Vec<u32>>,
+    extinct_at: Option<u32>,
+    /// Bounding box of the cells the last `step` changed, or `None` when
+    /// that isn't known (after an edit, a rule change or another kind of
+    /// tick) and the next `step` must recompute the whole grid.
+    dirty: Option<Region>,
+    recenter: bool,
+    /// When non-zero, `tick` grows the grid by this many cells on every side
+    /// whenever a live cell comes within that distance of an edge.
+    auto_grow_margin: u32,
+    cells_version: u32,
+    /// Number of live cells, kept in step with every change to `cells`.
+    live_count: u32,
+    /// Durations of the latest ticks in milliseconds, oldest first, holding
+    /// at most `tick_times_limit`; `0` turns timing off.
+    tick_times: VecDeque<f64>,
+    tick_times_limit: usize,
+    /// Flat indices of the cells whose state the last tick changed.
+    changed: Vec<u32>,
+    /// `(generation, population)` of the latest generations, oldest first.
+    populations: VecDeque<(u32, u32)>,
+    /// Generations kept by `tick_generations_into_history`, oldest first.
+    recording: VecDeque<Snapshot>,
+}
+
+impl Engine {
+    pub(crate) fn empty(width: u32, height: u32) -> Engine {
+        Engine {
+            width,
+            height,
+            cells: vec![Cell::Dead; (width * height) as usize],
+            generation: 0,
+            rule: Rule::conway(),
+            activity: vec![0; (width * height) as usize],
+            ticks_per_second: 60.0,
+            pending_ms: 0.0,
+            kernel: MOORE_KERNEL,
+            boundary: Boundary::Toroidal,
+            torus_offset: (0, 0),
+            escaped: 0,
+            escaped_per_edge: [0; 4],
+            history: VecDeque::new(),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            ages: None,
+            extinct_at: None,
+            dirty: None,
+            recenter: false,
+            auto_grow_margin: 0,
+            cells_version: 0,
+            live_count: 0,
+            tick_times: VecDeque::new(),
+            tick_times_limit: 0,
+            changed: Vec::new(),
+            populations: VecDeque::new(),
+            recording: VecDeque::new(),
+        }
+    }
+    fn from_pattern(pattern: formats::Pattern) -> Engine {
+        let mut universe = Engine::empty(pattern.width, pattern.height);
+        universe.set_cells(&pattern.cells);
+        if let Some(rule) = pattern.rule {
+            universe.rule = rule;
+        }
+        universe
+    }
+    pub fn get_index(&self, row: u32, column: u32) -> usize {
+        (row * self.width + column) as usize
+    }
+    /// How many of the 8 positions around `(row, column)` hold a live cell.
+    /// On a torus less than 3 cells wide or tall, several positions wrap
+    /// onto the same cell, which then counts once for each of them, just as
+    /// its copies would on the infinite plane the torus tiles. That can
+    /// include the cell itself; only the center position is skipped.
+    pub fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
+        let mut count = 0;
+        for delta_r in -1..=1 {
+            for delta_c in -1..=1 {
+                if delta_r == 0 && delta_c == 0 {
+                    // Exclude the actual cell
+                    continue;
+                }
+                if let Some((r, c)) = self.neighbor(row, column, delta_r, delta_c) {
+                    count += self.cells[self.get_index(r, c)] as u8;
+                }
+            }
+        }
+        count
+    }
+    /// Whether `(row, column)` has at least `n` live neighbors. Stops
+    /// looking as soon as the answer is known.
+    pub fn neighbors_alive_at_least(&self, row: u32, column: u32, n: u8) -> bool {
+        if n == 0 {
+            return true;
+        }
+        let mut count = 0;
+        for delta_r in -1..=1 {
+            for delta_c in -1..=1 {
+                if delta_r == 0 && delta_c == 0 {
+                    continue;
+                }
+                if let Some((r, c)) = self.neighbor(row, column, delta_r, delta_c) {
+                    count += self.cells[self.get_index(r, c)] as u8;
+                    if count >= n {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+    /// The cell `delta_r` rows and `delta_c` columns (each -1, 0 or 1) away
+    /// from `(row, column)`. On a torus this wraps around the grid, shifted
+    /// by `torus_offset` on a twisted one; under a dead boundary it's `None`
+    /// past the edge.
+    fn neighbor(&self, row: u32, column: u32, delta_r: i32, delta_c: i32) -> Option<(u32, u32)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let (height, width) = (self.height as i64, self.width as i64);
+        let r = row as i64 + delta_r as i64;
+        let c = column as i64 + delta_c as i64;
+        match self.boundary {
+            Boundary::Toroidal => {
+                // Crossing the top or bottom edge shifts the column and
+                // crossing the left or right edge shifts the row.
+                let (offset_r, offset_c) = self.torus_offset;
+                let r_shifted = r + c.div_euclid(width) * offset_r as i64;
+                let c_shifted = c + r.div_euclid(height) * offset_c as i64;
+                Some((
+                    r_shifted.rem_euclid(height) as u32,
+                    c_shifted.rem_euclid(width) as u32,
+                ))
+            }
+            Boundary::Dead if r < 0 || r >= height || c < 0 || c >= width => None,
+            Boundary::Dead => Some((r as u32, c as u32)),
+        }
+    }
+    fn or_reflection(&mut self, flip_rows: bool, flip_cols: bool) {
+        let original = self.cells.clone();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let src_row = if flip_rows {
+                    self.height - 1 - row
+                } else {
+                    row
+                };
+                let src_col = if flip_cols { self.width - 1 - col } else { col };
+                if original[self.get_index(src_row, src_col)] == Cell::Alive {
+                    let idx = self.get_index(row, col);
+                    self.write_cell(idx, Cell::Alive);
+                }
+            }
+        }
+    }
+    /// Advances one generation, returning whether any cell changed.
+    fn step(&mut self) -> bool {
+        let _timer = Timer::new("Engine::tick");
+        // self.call_api();
+        if self.cells.is_empty() {
+            return false;
+        }
+        let stopwatch = if self.tick_times_limit > 0 {
+            Some(Stopwatch::start())
+        } else {
+            None
+        };
+        if self.boundary == Boundary::Dead {
+            let (total, per_edge) = self.edge_deaths();
+            self.escaped += total;
+            for (count, deaths) in self.escaped_per_edge.iter_mut().zip(per_edge.iter()) {
+                *count += deaths;
+            }
+        }
+        let next = {
+            let _timer = Timer::new("new generation");
+            match self.step_region() {
+                Some(region) => self.next_generation_in(region),
+                // The row sums of `next_generation` assume wrapping doesn't
+                // shift anything.
+                None if self.torus_offset != (0, 0) => self.next_generation_in(Region {
+                    top: 0,
+                    left: 0,
+                    bottom: self.height,
+                    right: self.width,
+                }),
+                None => self.next_generation(),
+            }
+        };
+        let changed = self.commit(next);
+        if self.recenter {
+            self.center_population();
+        }
+        if self.auto_grow_margin > 0 && self.near_edge(self.auto_grow_margin) {
+            self.grow(self.auto_grow_margin);
+        }
+        if let Some(stopwatch) = stopwatch {
+            self.tick_times.push_back(stopwatch.elapsed_ms());
+            while self.tick_times.len() > self.tick_times_limit {
+                self.tick_times.pop_front();
+            }
+        }
+        changed
+    }
+    /// Computes the next generation in a single pass over the rows.
+    ///
+    /// Instead of a full grid of neighbor counts, this keeps the horizontal
+    /// sums (a cell plus its left and right neighbors) of just three rows:
+    /// the one above, the current one and the one below. A cell's neighbor
+    /// count is the sum of the three values in its column minus the cell
+    /// itself. Moving down a row drops the oldest sums and computes the
+    /// sums of the new row below.
+    fn next_generation(&self) -> Vec<Cell> {
+        let width = self.width as usize;
+        let height = self.height as i64;
+        let mut rows = [vec![0u8; width], vec![0u8; width], vec![0u8; width]];
+        self.fill_row_sums(-1, &mut rows[0]);
+        self.fill_row_sums(0, &mut rows[1]);
+        self.fill_row_sums(1, &mut rows[2]);
+        let mut next = Vec::with_capacity(self.cells.len());
+        for row in 0..height {
+            if row > 0 {
+                rows.rotate_left(1);
+                self.fill_row_sums(row + 1, &mut rows[2]);
+            }
+            let start = row as usize * width;
+            for (col, &cell) in self.cells[start..start + width].iter().enumerate() {
+                let count = rows[0][col] + rows[1][col] + rows[2][col] - cell as u8;
+                next.push(self.rule.next(cell, count));
+            }
+        }
+        next
+    }
+    /// Writes the horizontal sums of `row` into `sums`; see
+    /// `next_generation`. Rows past the edge wrap around on a torus and
+    /// are all dead under a dead boundary.
+    fn fill_row_sums(&self, row: i64, sums: &mut [u8]) {
+        let height = self.height as i64;
+        let row = match self.boundary {
+            Boundary::Toroidal => (row + height) % height,
+            Boundary::Dead if row < 0 || row >= height => {
+                sums.iter_mut().for_each(|sum| *sum = 0);
+                return;
+            }
+            Boundary::Dead => row,
+        };
+        let row = row as u32;
+        for col in 0..self.width {
+            let mut sum = 0;
+            for delta_c in -1..=1 {
+                if let Some((r, c)) = self.neighbor(row, col, 0, delta_c) {
+                    sum += self.cells[self.get_index(r, c)] as u8;
+                }
+            }
+            sums[col as usize] = sum;
+        }
+    }
+    /// The cells that can change on this `step`, or `None` for the whole
+    /// grid.
+    ///
+    /// A cell whose 3x3 neighborhood didn't change last generation gets the
+    /// same result from the rule as it did then, so it keeps its state. Only
+    /// the last step's changes grown by one cell need recomputing. Regions
+    /// that would wrap around a torus fall back to the whole grid.
+    fn step_region(&self) -> Option<Region> {
+        let dirty = self.dirty?;
+        if dirty.top >= dirty.bottom || dirty.left >= dirty.right {
+            return Some(dirty);
+        }
+        let touches_edge = dirty.top == 0
+            || dirty.left == 0
+            || dirty.bottom == self.height
+            || dirty.right == self.width;
+        if touches_edge && self.boundary == Boundary::Toroidal {
+            return None;
+        }
+        Some(Region {
+            top: dirty.top.saturating_sub(1),
+            left: dirty.left.saturating_sub(1),
+            bottom: (dirty.bottom + 1).min(self.height),
+            right: (dirty.right + 1).min(self.width),
+        })
+    }
+    /// Like `next_generation`, but only applies the rule inside `region`;
+    /// every other cell is copied unchanged.
+    fn next_generation_in(&self, region: Region) -> Vec<Cell> {
+        let mut next = self.cells.clone();
+        for row in region.top..region.bottom {
+            for col in region.left..region.right {
+                let idx = self.get_index(row, col);
+                next[idx] = self
+                    .rule
+                    .next(self.cells[idx], self.live_neighbor_count(row, col));
+            }
+        }
+        next
+    }
+    /// Shifts the grid so the live cells' center of mass lands on the
+    /// middle cell; see `set_recenter`.
+    fn center_population(&mut self) {
+        let (mut rows, mut cols, mut count) = (0u64, 0u64, 0u64);
+        for (idx, _) in self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == Cell::Alive)
+        {
+            rows += idx as u64 / self.width as u64;
+            cols += idx as u64 % self.width as u64;
+            count += 1;
+        }
+        if count == 0 {
+            return;
+        }
+        let mean_row = (rows as f64 / count as f64).round() as i64;
+        let mean_col = (cols as f64 / count as f64).round() as i64;
+        let delta_r = self.height as i64 / 2 - mean_row;
+        let delta_c = self.width as i64 / 2 - mean_col;
+        if delta_r != 0 || delta_c != 0 {
+            self.roll(delta_r, delta_c);
+        }
+    }
+    /// Rebuilds the grid as `width` x `height`, taking each cell from the
+    /// `(row, column)` that `source` maps its own coordinates to. Like a
+    /// resize, this resets activity, ages and undo history.
+    fn reorient<F: Fn(u32, u32) -> (u32, u32)>(&mut self, width: u32, height: u32, source: F) {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for row in 0..height {
+            for col in 0..width {
+                let (src_row, src_col) = source(row, col);
+                cells.push(self.cells[self.get_index(src_row, src_col)]);
+            }
+        }
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.reset_cell_state();
+    }
+    /// Moves every cell, along with its activity count and age, `delta_r`
+    /// rows down and `delta_c` columns right, wrapping around the edges.
+    fn roll(&mut self, delta_r: i64, delta_c: i64) {
+        let cells = self.rolled(&self.cells, delta_r, delta_c);
+        self.cells.copy_from_slice(&cells);
+        self.activity = self.rolled(&self.activity, delta_r, delta_c);
+        if let Some(ages) = &self.ages {
+            self.ages = Some(self.rolled(ages, delta_r, delta_c));
+        }
+        // Every cell may now show something different.
+        self.changed = (0..self.cells.len() as u32).collect();
+        self.dirty = None;
+    }
+    fn rolled<T: Copy>(&self, values: &[T], delta_r: i64, delta_c: i64) -> Vec<T> {
+        let (height, width) = (self.height as i64, self.width as i64);
+        let mut out = values.to_vec();
+        for row in 0..height {
+            for col in 0..width {
+                let to_row = (row + delta_r).rem_euclid(height);
+                let to_col = (col + delta_c).rem_euclid(width);
+                out[(to_row * width + to_col) as usize] = values[(row * width + col) as usize];
+            }
+        }
+        out
+    }
+    /// `values`, laid out like the grid, surrounded by `margin` rows and
+    /// columns of `fill`.
+    fn padded<T: Copy>(&self, values: &[T], margin: u32, fill: T) -> Vec<T> {
+        let width = (self.width + 2 * margin) as usize;
+        let mut out = vec![fill; width * (self.height + 2 * margin) as usize];
+        for (row, line) in values.chunks(self.width.max(1) as usize).enumerate() {
+            let start = (row + margin as usize) * width + margin as usize;
+            out[start..start + line.len()].copy_from_slice(line);
+        }
+        out
+    }
+    /// Live cells on the outermost ring that die this generation, in total
+    /// and on the top, right, bottom and left edges. Corner cells count
+    /// towards both of their edges. Under a dead boundary this approximates
+    /// cells lost to the edge, e.g. a glider running off the grid; it also
+    /// counts ordinary deaths that happen to occur on the edge.
+    fn edge_deaths(&self) -> (u32, [u32; 4]) {
+        let mut total = 0;
+        let mut per_edge = [0; 4];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let edges = [
+                    row == 0,
+                    col == self.width - 1,
+                    row == self.height - 1,
+                    col == 0,
+                ];
+                if edges.iter().any(|&on_edge| on_edge)
+                    && self.cells[self.get_index(row, col)] == Cell::Alive
+                    && self
+                        .rule
+                        .next(Cell::Alive, self.live_neighbor_count(row, col))
+                        == Cell::Dead
+                {
+                    total += 1;
+                    for (count, &on_edge) in per_edge.iter_mut().zip(edges.iter()) {
+                        *count += on_edge as u32;
+                    }
+                }
+            }
+        }
+        (total, per_edge)
+    }
+    /// Replaces every cell with `next_cell(index, cell)`, counting the
+    /// result as a new generation. Returns whether any cell changed.
+    fn advance<F: Fn(usize, Cell) -> Cell>(&mut self, next_cell: F) -> bool {
+        if self.cells.is_empty() {
+            return false;
+        }
+        let next = {
+            let _timer = Timer::new("new generation");
+            self.cells
+                .iter()
+                .enumerate()
+                .map(|(idx, &cell)| next_cell(idx, cell))
+                .collect()
+        };
+        let changed = self.commit(next);
+        self.dirty = None;
+        changed
+    }
+    /// Makes `next` the current generation, updating the per-cell
+    /// statistics, the dirty region and the undo history. Returns whether
+    /// any cell changed.
+    fn commit(&mut self, mut next: Vec<Cell>) -> bool {
+        let mut changed = false;
+        let mut dirty = Region {
+            top: self.height,
+            left: self.width,
+            bottom: 0,
+            right: 0,
+        };
+        let mut live_count = 0;
+        self.changed.clear();
+        self.record_population();
+        for (idx, (&cell, &next_cell)) in self.cells.iter().zip(next.iter()).enumerate() {
+            live_count += next_cell as u32;
+            if next_cell != cell {
+                changed = true;
+                self.activity[idx] += 1;
+                self.changed.push(idx as u32);
+                let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+                dirty.top = dirty.top.min(row);
+                dirty.left = dirty.left.min(col);
+                dirty.bottom = dirty.bottom.max(row + 1);
+                dirty.right = dirty.right.max(col + 1);
+            }
+            if let Some(ages) = &mut self.ages {
+                ages[idx] = if next_cell != cell {
+                    0
+                } else {
+                    ages[idx].saturating_add(1)
+                };
+            }
+        }
+        let _timer = Timer::new("archive old cells");
+        // Swap contents rather than buffers so `cells()` stays valid.
+        self.cells.swap_with_slice(&mut next);
+        self.push_history(next);
+        self.live_count = live_count;
+        self.generation += 1;
+        self.record_population();
+        if self.extinct_at.is_none() && live_count == 0 {
+            self.extinct_at = Some(self.generation);
+        }
+        self.dirty = Some(dirty);
+        changed
+    }
+    /// Remembers the current population as that of the current generation,
+    /// replacing what was recorded for it or any later generation (after an
+    /// edit or `step_back`).
+    fn record_population(&mut self) {
+        while self
+            .populations
+            .back()
+            .is_some_and(|&(generation, _)| generation >= self.generation)
+        {
+            self.populations.pop_back();
+        }
+        self.populations
+            .push_back((self.generation, self.live_count));
+        while self.populations.len() > POPULATION_HISTORY_LIMIT {
+            self.populations.pop_front();
+        }
+    }
+    /// A copy of the grid and its boundary running under `rule`, without
+    /// history, statistics or the tick callback.
+    fn detached_copy(&self, rule: Rule) -> Engine {
+        let mut copy = Engine::empty(self.width, self.height);
+        copy.cells.clone_from(&self.cells);
+        copy.live_count = self.live_count;
+        copy.generation = self.generation;
+        copy.rule = rule;
+        copy.boundary = self.boundary;
+        copy.torus_offset = self.torus_offset;
+        copy.history_limit = 0;
+        copy
+    }
+    /// Saves the current state so a following edit can be undone.
+    fn save_snapshot(&mut self) {
+        let cells = self.cells.clone();
+        self.push_history(cells);
+    }
+    fn push_history(&mut self, cells: Vec<Cell>) {
+        if self.history_limit == 0 {
+            return;
+        }
+        self.history.push_back(Snapshot {
+            cells,
+            generation: self.generation,
+        });
+        while self.history.len() > self.history_limit {
+            self.history.pop_front();
+        }
+    }
+    /// Sum of each cell's neighborhood weighted by `kernel`, in the same
+    /// layout as `cells()`.
+    fn weighted_sums(&self) -> Vec<i32> {
+        let mut sums = vec![0; self.cells.len()];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Dead {
+                    continue;
+                }
+                // A live cell contributes to the cell at offset (dr, dc)
+                // with that cell's weight for the opposite offset.
+                for delta_r in -1..=1 {
+                    for delta_c in -1..=1 {
+                        if let Some((r, c)) = self.neighbor(row, col, delta_r, delta_c) {
+                            let weight = self.kernel[((1 - delta_r) * 3 + (1 - delta_c)) as usize];
+                            sums[self.get_index(r, c)] += weight;
+                        }
+                    }
+                }
+            }
+        }
+        sums
+    }
+    /// The live neighbors of every cell as an `isotropic` neighborhood mask.
+    fn neighborhoods(&self) -> Vec<u16> {
+        let mut neighborhoods = vec![0; self.cells.len()];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Dead {
+                    continue;
+                }
+                // Seen from the cell at offset (dr, dc), this one sits at
+                // the opposite offset.
+                for delta_r in -1..=1 {
+                    for delta_c in -1..=1 {
+                        if delta_r == 0 && delta_c == 0 {
+                            continue;
+                        }
+                        if let Some((r, c)) = self.neighbor(row, col, delta_r, delta_c) {
+                            neighborhoods[self.get_index(r, c)] |=
+                                isotropic::neighbor_bit(-delta_r, -delta_c);
+                        }
+                    }
+                }
+            }
+        }
+        neighborhoods
+    }
+    /// Whether the object made of `cells` moves; see `detect_spaceships`.
+    fn is_spaceship(&self, cells: &[(i64, i64)], max_steps: u32) -> bool {
+        let shape = patterns::normalize(cells);
+        let height = shape.iter().map(|&(r, _)| r + 1).max().unwrap_or(0);
+        let width = shape.iter().map(|&(_, c)| c + 1).max().unwrap_or(0);
+        // Enough room on the torus that the object doesn't run into itself
+        // before it has had a chance to recur.
+        let margin = height.max(width) + 2;
+        let mut alone = Engine::empty(width + 2 * margin, height + 2 * margin);
+        alone.rule = self.rule;
+        alone.history_limit = 0;
+        let placed: Vec<(u32, u32)> = shape
+            .iter()
+            .map(|&(r, c)| (r + margin, c + margin))
+            .collect();
+        alone.set_cells(&placed);
+        for _ in 0..max_steps {
+            alone.step();
+            let live: Vec<(i64, i64)> = alone
+                .alive_indices()
+                .iter()
+                .map(|&idx| ((idx / alone.width) as i64, (idx % alone.width) as i64))
+                .collect();
+            if live.is_empty() {
+                return false;
+            }
+            if patterns::normalize(&live) == shape {
+                let top = live.iter().map(|&(r, _)| r).min();
+                let left = live.iter().map(|&(_, c)| c).min();
+                return (top, left) != (Some(margin as i64), Some(margin as i64));
+            }
+        }
+        false
+    }
+    /// Whether `cells` (normalized to start at `(0, 0)`) appear anywhere on
+    /// the torus surrounded by a ring of dead cells.
+    fn find_isolated(&self, cells: &[(u32, u32)]) -> bool {
+        let height = cells.iter().map(|p| p.0).max().unwrap_or(0) + 1;
+        let width = cells.iter().map(|p| p.1).max().unwrap_or(0) + 1;
+        if height + 2 > self.height || width + 2 > self.width {
+            return false;
+        }
+        (0..self.height).any(|top| {
+            (0..self.width).any(|left| {
+                // Scan the bounding box plus its border, starting one row
+                // and column before `(top, left)`.
+                (0..height + 2).all(|r| {
+                    (0..width + 2).all(|c| {
+                        let row = (top + self.height - 1 + r) % self.height;
+                        let col = (left + self.width - 1 + c) % self.width;
+                        let inside = r >= 1 && c >= 1 && cells.contains(&(r - 1, c - 1));
+                        (self.cells[self.get_index(row, col)] == Cell::Alive) == inside
+                    })
+                })
+            })
+        })
+    }
+    /// The groups of live cells that touch each other, including
+    /// diagonally, in the order their first cell appears in `cells()`. Each
+    /// group's cells are given relative to that first cell, so a group that
+    /// wraps around the torus stays in one piece.
+    fn components(&self) -> Vec<Vec<(i64, i64)>> {
+        let mut seen = vec![false; self.cells.len()];
+        let mut components = Vec::new();
+        for start in 0..self.cells.len() {
+            if seen[start] || self.cells[start] == Cell::Dead {
+                continue;
+            }
+            seen[start] = true;
+            let (row, col) = (start as u32 / self.width, start as u32 % self.width);
+            let mut stack = vec![(row, col, 0i64, 0i64)];
+            let mut component = Vec::new();
+            while let Some((row, col, rel_r, rel_c)) = stack.pop() {
+                component.push((rel_r, rel_c));
+                for delta_r in -1..=1 {
+                    for delta_c in -1..=1 {
+                        if let Some((r, c)) = self.neighbor(row, col, delta_r, delta_c) {
+                            let idx = self.get_index(r, c);
+                            if !seen[idx] && self.cells[idx] == Cell::Alive {
+                                seen[idx] = true;
+                                stack.push((r, c, rel_r + delta_r as i64, rel_c + delta_c as i64));
+                            }
+                        }
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+    /// Starting ages when tracking is switched on: live cells count as just
+    /// born, dead cells as having been dead forever.
+    fn initial_ages(&self) -> Vec<u32> {
+        self.cells
+            .iter()
+            .map(|&cell| if cell == Cell::Alive { 0 } else { u32::MAX })
+            .collect()
+    }
+    /// Forgets statistics about the run so far that an edit invalidates.
+    fn edited(&mut self) {
+        self.extinct_at = None;
+        self.dirty = None;
+    }
+    /// Sets cell `idx` to `cell`, keeping `live_count` up to date.
+    fn write_cell(&mut self, idx: usize, cell: Cell) {
+        self.live_count = self.live_count + cell as u32 - self.cells[idx] as u32;
+        self.cells[idx] = cell;
+    }
+    /// Recomputes `live_count` after a bulk change to `cells`.
+    pub(crate) fn recount(&mut self) {
+        self.live_count = self.cells.iter().map(|&cell| cell as u32).sum();
+    }
+    /// Resets everything tracked per cell after the grid was resized.
+    fn reset_cell_state(&mut self) {
+        self.edited();
+        self.cells_version = self.cells_version.wrapping_add(1);
+        self.recount();
+        self.activity = vec![0; self.cells.len()];
+        self.changed.clear();
+        self.populations.clear();
+        self.recording.clear();
+        self.history.clear();
+        if self.ages.is_some() {
+            self.ages = Some(self.initial_ages());
+        }
+    }
+    /// The smallest rectangle holding every live cell, or `None` when there
+    /// are none.
+    fn live_bounds(&self) -> Option<Region> {
+        let mut bounds: Option<Region> = None;
+        for (idx, _) in self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == Cell::Alive)
+        {
+            let (row, col) = (idx as u32 / self.width, idx as u32 % self.width);
+            bounds = Some(match bounds {
+                None => Region {
+                    top: row,
+                    left: col,
+                    bottom: row + 1,
+                    right: col + 1,
+                },
+                Some(region) => Region {
+                    top: region.top.min(row),
+                    left: region.left.min(col),
+                    bottom: region.bottom.max(row + 1),
+                    right: region.right.max(col + 1),
+                },
+            });
+        }
+        bounds
+    }
+    /// `cells`, laid out like this grid, as an RLE pattern with this rule,
+    /// wrapping lines at 70 characters.
+    fn rle_frame(&self, cells: &[Cell]) -> String {
+        let mut out = format!(
+            "x = {}, y = {}, rule = {}\n",
+            self.width,
+            self.height,
+            self.rule.notation()
+        );
+        let mut line = String::new();
+        let mut emit = |count: usize, tag: char| {
+            let token = match count {
+                1 => tag.to_string(),
+                _ => format!("{}{}", count, tag),
+            };
+            if line.len() + token.len() > 70 {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        };
+        let mut pending_rows = 0;
+        for row in cells.chunks(self.width.max(1) as usize) {
+            // Trailing dead cells of a row are implied by the next `$`.
+            let used = row.iter().rposition(|&cell| cell == Cell::Alive);
+            if let Some(last) = used {
+                if pending_rows > 0 {
+                    emit(pending_rows, '$');
+                }
+                let mut runs = row[..=last].iter().peekable();
+                while let Some(&cell) = runs.next() {
+                    let mut count = 1;
+                    while runs.peek() == Some(&&cell) {
+                        runs.next();
+                        count += 1;
+                    }
+                    emit(count, if cell == Cell::Alive { 'o' } else { 'b' });
+                }
+                pending_rows = 0;
+            }
+            pending_rows += 1;
+        }
+        emit(1, '!');
+        out.push_str(&line);
+        out.push('\n');
+        out
+    }
+    /// Whether any live cell lies within `margin` cells of an edge.
+    fn near_edge(&self, margin: u32) -> bool {
+        self.live_bounds().is_some_and(|bounds| {
+            bounds.top < margin
+                || bounds.left < margin
+                || bounds.bottom + margin > self.height
+                || bounds.right + margin > self.width
+        })
+    }
+    /// The mean `(row, column)` of the live cells, or `None` when there are
+    /// none. Coordinates aren't unwrapped, so on a torus a pattern straddling
+    /// an edge averages to somewhere in the middle.
+    fn center_of_mass(&self) -> Option<(f64, f64)> {
+        if self.live_count == 0 {
+            return None;
+        }
+        let (mut rows, mut cols) = (0.0, 0.0);
+        for idx in self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == Cell::Alive)
+            .map(|(idx, _)| idx as u32)
+        {
+            rows += f64::from(idx / self.width);
+            cols += f64::from(idx % self.width);
+        }
+        let count = f64::from(self.live_count);
+        Some((rows / count, cols / count))
+    }
+    /// Sets the 3x3 weights used by `tick_weighted`, row by row from the
+    /// north-west neighbor; the middle entry weights the cell itself.
+    pub fn set_kernel(&mut self, kernel: &[i32; 9]) {
+        self.kernel = *kernel;
+    }
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+    pub fn get_cells(&self) -> &[Cell] {
+        &self.cells
+    }
+    /// Sets the given `(row, column)` cells alive, skipping any that fall
+    /// outside the grid.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+        self.edited();
+        for (row, col) in cells.iter().cloned() {
+            if !self.is_within_bounds(row, col) {
+                continue;
+            }
+            let idx = self.get_index(row, col);
+            self.write_cell(idx, Cell::Alive);
+        }
+    }
+    pub fn new() -> Self {
+        let width = 64;
+        let height = 64;
+        let mut universe = Engine::empty(width, height);
+        universe.cells = (0..width * height)
+            .map(|i| {
+                if i % 2 == 0 || i % 7 == 0 {
+                    Cell::Alive
+                } else {
+                    Cell::Dead
+                }
+            })
+            .collect();
+        universe.recount();
+        universe
+    }
+    /// A 48x32 showcase of oscillators: a pulsar, a pentadecathlon, a toad
+    /// and a beacon from the pattern catalog, spaced so that none of them
+    /// interfere. The whole scene repeats every 30 generations.
+    pub fn prepopulate_common_oscillators() -> Engine {
+        let mut universe = Engine::empty(48, 32);
+        for &(name, row, col) in OSCILLATOR_SCENE {
+            universe
+                .insert_pattern(name, row, col)
+                .expect("scene patterns are in the catalog");
+        }
+        universe
+    }
+    /// Live neighbor count of every cell, in the same layout as `cells()`.
+    ///
+    /// Rather than asking each cell about its 8 neighbors, every live cell
+    /// adds one to the tally of each of its neighbors, so dead regions cost
+    /// nothing beyond the initial pass.
+    pub fn neighbor_counts(&self) -> Vec<u8> {
+        let mut counts = vec![0u8; self.cells.len()];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Dead {
+                    continue;
+                }
+                for delta_r in -1..=1 {
+                    for delta_c in -1..=1 {
+                        if delta_r == 0 && delta_c == 0 {
+                            continue;
+                        }
+                        if let Some((r, c)) = self.neighbor(row, col, delta_r, delta_c) {
+                            counts[self.get_index(r, c)] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        counts
+    }
+    /// How many cells have 0, 1, ..., 8 live neighbors, indexed by count.
+    pub fn neighbor_histogram(&self) -> Vec<u32> {
+        let mut histogram = vec![0; 9];
+        for count in self.neighbor_counts() {
+            histogram[count as usize] += 1;
+        }
+        histogram
+    }
+    /// Copies the `height` x `width` region whose top-left corner is at
+    /// `(row, col)` into a new universe. The region is clipped to the grid;
+    /// an origin outside the grid or an empty region is an error.
+    pub fn subgrid(&self, row: u32, col: u32, height: u32, width: u32) -> Result<Engine, String> {
+        if !self.is_within_bounds(row, col) {
+            return Err("subgrid origin is outside the universe".to_string());
+        }
+        let height = height.min(self.height - row);
+        let width = width.min(self.width - col);
+        if height == 0 || width == 0 {
+            return Err("subgrid must be at least 1x1".to_string());
+        }
+        let mut sub = Engine::empty(width, height);
+        for r in 0..height {
+            for c in 0..width {
+                let idx = sub.get_index(r, c);
+                sub.write_cell(idx, self.cells[self.get_index(row + r, col + c)]);
+            }
+        }
+        Ok(sub)
+    }
+    /// Bitmask of which neighbors of `(row, column)` are alive. Bit 0 is
+    /// north and the following bits go clockwise: NE, E, SE, S, SW, W, NW.
+    pub fn alive_neighbors_mask(&self, row: u32, column: u32) -> u8 {
+        let offsets = [
+            (-1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+            (1, 0),
+            (1, -1),
+            (0, -1),
+            (-1, -1),
+        ];
+        let mut mask = 0;
+        for (bit, &(delta_r, delta_c)) in offsets.iter().enumerate() {
+            if let Some((r, c)) = self.neighbor(row, column, delta_r, delta_c) {
+                if self.cells[self.get_index(r, c)] == Cell::Alive {
+                    mask |= 1 << bit;
+                }
+            }
+        }
+        mask
+    }
+    /// Builds a universe from an RLE pattern, sized to the pattern's
+    /// declared `x`/`y` dimensions, under the rule its header names (Life
+    /// if it names none).
+    pub fn from_rle(text: &str) -> Result<Engine, String> {
+        formats::rle::parse(text).map(Engine::from_pattern)
+    }
+    /// Serializes the grid, generation, rule and boundary to JSON, so that
+    /// `load` can resume the simulation exactly where it left off. Undo
+    /// history and statistics such as `activity` aren't saved.
+    pub fn save(&self) -> String {
+        serde_json::to_string(&save::SaveFile::from_engine(self))
+            .expect("save files always serialize")
+    }
+    /// Restores a universe written by `save`.
+    pub fn load(json: &str) -> Result<Engine, String> {
+        let file: save::SaveFile =
+            serde_json::from_str(json).map_err(|err| format!("invalid save file: {}", err))?;
+        file.into_engine()
+    }
+    /// Builds a `width` x `height` universe from RGBA pixels laid out like
+    /// `ImageData`, one pixel per cell. A cell is alive when its pixel's
+    /// luminance (Rec. 601, from 0 to 255, ignoring alpha) exceeds
+    /// `threshold`. Errors unless there are exactly `width * height` pixels.
+    pub fn from_image_data(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        threshold: u8,
+    ) -> Result<Engine, String> {
+        check_dimensions(width, height)?;
+        let expected = width as u64 * height as u64 * 4;
+        if rgba.len() as u64 != expected {
+            return Err(format!(
+                "expected {} bytes for a {}x{} image, got {}",
+                expected,
+                width,
+                height,
+                rgba.len()
+            ));
+        }
+        let mut engine = Engine::empty(width, height);
+        for (idx, pixel) in rgba.chunks(4).enumerate() {
+            let luminance =
+                0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64;
+            if luminance > threshold as f64 {
+                engine.write_cell(idx, Cell::Alive);
+            }
+        }
+        Ok(engine)
+    }
+    /// Builds a universe from comma-separated `0`/`1` rows, the inverse of
+    /// `export_csv`. Every row must have the same number of cells.
+    pub fn from_csv(text: &str) -> Result<Engine, String> {
+        formats::csv::parse(text).map(Engine::from_pattern)
+    }
+    /// Builds a universe from a Life 1.05 pattern (see `to_lif_105`), sized
+    /// to the bounding box of its live cells, under the rule its `#R` line
+    /// names. Overlapping `#P` blocks are combined.
+    pub fn from_lif_105(text: &str) -> Result<Engine, String> {
+        formats::life105::parse(text).map(Engine::from_pattern)
+    }
+    /// Builds a universe from a LifeWiki `.cells` file: `!` comment lines
+    /// followed by a grid of `.` and `O`. The grid is as wide as its longest
+    /// line, with shorter lines padded with dead cells.
+    pub fn from_cells_format(text: &str) -> Result<Engine, String> {
+        formats::plaintext::parse(text).map(Engine::from_pattern)
+    }
+    /// Loads a pattern in whichever supported format it appears to be: RLE,
+    /// Life 1.06 or a plain text grid.
+    pub fn parse(text: &str) -> Result<Engine, String> {
+        formats::parse_any(text).map(Engine::from_pattern)
+    }
+    /// Fills the `height` x `width` rectangle at `(row, col)` with random
+    /// cells, each alive with probability `density`. Cells outside the
+    /// rectangle are left alone; the rectangle is clipped to the grid.
+    pub fn randomize_region(
+        &mut self,
+        row: u32,
+        col: u32,
+        height: u32,
+        width: u32,
+        seed: u64,
+        density: f64,
+    ) {
+        self.save_snapshot();
+        self.edited();
+        let mut rng = rng::Rng::new(seed);
+        let row_end = row.saturating_add(height).min(self.height);
+        let col_end = col.saturating_add(width).min(self.width);
+        for r in row..row_end {
+            for c in col..col_end {
+                let idx = self.get_index(r, c);
+                let cell = if rng.next_f64() < density {
+                    Cell::Alive
+                } else {
+                    Cell::Dead
+                };
+                self.write_cell(idx, cell);
+            }
+        }
+    }
+    /// Fills the whole grid with random cells; see `randomize_region`.
+    pub fn randomize(&mut self, seed: u64, density: f64) {
+        self.randomize_region(0, 0, self.height, self.width, seed, density);
+    }
+    /// Clears the grid and draws a random walk of `steps` moves on it,
+    /// starting from the middle cell and turning on every cell it visits.
+    /// Each move goes one cell up, down, left or right; the walk wraps
+    /// around a toroidal boundary and stays put rather than cross a dead
+    /// one. The same `seed` always draws the same walk.
+    pub fn random_walk_seed(&mut self, steps: u32, seed: u64) {
+        if self.cells.is_empty() {
+            return;
+        }
+        self.clear();
+        let mut rng = rng::Rng::new(seed);
+        let (mut row, mut col) = (self.height / 2, self.width / 2);
+        let idx = self.get_index(row, col);
+        self.write_cell(idx, Cell::Alive);
+        for _ in 0..steps {
+            let (delta_r, delta_c) =
+                [(-1, 0), (1, 0), (0, -1), (0, 1)][(rng.next_u64() % 4) as usize];
+            if let Some((r, c)) = self.neighbor(row, col, delta_r, delta_c) {
+                row = r;
+                col = c;
+                let idx = self.get_index(row, col);
+                self.write_cell(idx, Cell::Alive);
+            }
+        }
+    }
+    /// Turns on `count` distinct dead cells picked at random, or every dead
+    /// cell if there are fewer, so that on an empty grid the population
+    /// ends up exactly `count`. The same `seed` always picks the same cells.
+    pub fn scatter(&mut self, count: u32, seed: u64) {
+        self.save_snapshot();
+        self.edited();
+        let mut rng = rng::Rng::new(seed);
+        let mut dead: Vec<usize> = (0..self.cells.len())
+            .filter(|&idx| self.cells[idx] == Cell::Dead)
+            .collect();
+        let count = (count as usize).min(dead.len());
+        // A partial Fisher-Yates shuffle picks the first `count` cells.
+        for i in 0..count {
+            let j = i + (rng.next_u64() % (dead.len() - i) as u64) as usize;
+            dead.swap(i, j);
+            self.write_cell(dead[i], Cell::Alive);
+        }
+    }
+    /// Randomizes the grid like `randomize`, at a density of one half, with
+    /// a seed hashed from `data`, so any input (a name, a URL, a file) maps
+    /// to the same pattern every time.
+    pub fn seed_from_bytes(&mut self, data: &[u8]) {
+        self.randomize(fnv1a(data.iter().cloned()), SOUP_DENSITY);
+    }
+    /// Runs `trials` random `width` x `height` soups (see `randomize`) for
+    /// `generations` ticks each and returns the peak population of every
+    /// trial. Each trial's seed is drawn from a generator seeded with
+    /// `seed`, so the same arguments always give the same results. A soup
+    /// larger than the `enforce_max_dimensions` limit is an error.
+    pub fn soup_search(
+        width: u32,
+        height: u32,
+        generations: u32,
+        trials: u32,
+        seed: u64,
+    ) -> Result<Vec<u32>, String> {
+        check_dimensions(width, height)?;
+        let mut seeds = rng::Rng::new(seed);
+        let peaks = (0..trials)
+            .map(|_| {
+                let mut soup = Engine::empty(width, height);
+                soup.history_limit = 0;
+                soup.randomize(seeds.next_u64(), SOUP_DENSITY);
+                let mut peak = soup.live_count;
+                for _ in 0..generations {
+                    soup.step();
+                    peak = peak.max(soup.live_count);
+                }
+                peak
+            })
+            .collect();
+        Ok(peaks)
+    }
+    /// How long random soups last before settling, for studying
+    /// methuselahs. Runs `trials` soups of this universe's size, rule and
+    /// boundary (seeded like `soup_search`) for up to `generations` ticks
+    /// each, and counts them by the generation at which they settled into a
+    /// still life, an oscillator or extinction: entry `g` of the result is
+    /// the number that settled at generation `g`, and the last entry (at
+    /// index `generations`) the number that hadn't settled by the end.
+    pub fn lifespan_histogram(&self, trials: u32, generations: u32, seed: u64) -> Vec<u32> {
+        let mut histogram = vec![0; generations as usize + 1];
+        let mut seeds = rng::Rng::new(seed);
+        for _ in 0..trials {
+            let mut soup = self.detached_copy(self.rule);
+            soup.generation = 0;
+            soup.randomize(seeds.next_u64(), SOUP_DENSITY);
+            let lifespan = soup
+                .find_cycle(generations)
+                .map_or(generations, |(onset, _)| onset);
+            histogram[lifespan as usize] += 1;
+        }
+        histogram
+    }
+    /// Runs a `width` x `height` soup randomized like `randomize(seed,
+    /// density)` for `steps` generations and summarizes the run as JSON:
+    /// `final_population`, `max_population` (counting the starting one),
+    /// `generation` and `detected_period`, the period of the first state
+    /// seen twice during the run or `null` if none was.
+    pub fn simulate_to_json(
+        width: u32,
+        height: u32,
+        seed: u64,
+        density: f64,
+        steps: u32,
+    ) -> Result<String, String> {
+        check_dimensions(width, height)?;
+        let mut soup = Engine::empty(width, height);
+        soup.history_limit = 0;
+        soup.randomize(seed, density);
+        let mut max_population = soup.live_count;
+        let mut seen = HashMap::new();
+        seen.insert(soup.state_hash(), soup.generation);
+        let mut period = None;
+        for _ in 0..steps {
+            soup.step();
+            max_population = max_population.max(soup.live_count);
+            if period.is_none() {
+                if let Some(onset) = seen.insert(soup.state_hash(), soup.generation) {
+                    period = Some(soup.generation - onset);
+                }
+            }
+        }
+        Ok(serde_json::json!({
+            "final_population": soup.live_count,
+            "max_population": max_population,
+            "generation": soup.generation,
+            "detected_period": period,
+        })
+        .to_string())
+    }
+    /// Roughly how tame `rule` (in `B3/S23` notation) is: the fraction of
+    /// `trials` random 32x32 soups on a torus that die out or settle into a
+    /// still life or oscillator within 100 ticks. Chaotic rules score near
+    /// `0`, rules where everything quickly dies or freezes near `1`. Seeded
+    /// like `soup_search`.
+    pub fn probe_rule_stability(rule: &str, trials: u32, seed: u64) -> Result<f64, String> {
+        let rule = Rule::parse(rule)?;
+        if trials == 0 {
+            return Ok(0.0);
+        }
+        let mut seeds = rng::Rng::new(seed);
+        let settled = (0..trials)
+            .filter(|_| {
+                let mut soup = Engine::empty(PROBE_SIZE, PROBE_SIZE);
+                soup.history_limit = 0;
+                soup.rule = rule;
+                soup.randomize(seeds.next_u64(), SOUP_DENSITY);
+                soup.find_cycle(PROBE_STEPS).is_some()
+            })
+            .count();
+        Ok(settled as f64 / trials as f64)
+    }
+    /// Sets each cell alive with its own probability `probs[index]`, laid
+    /// out like `cells()`, drawing from a generator seeded with `seed`.
+    /// Errors unless there is exactly one probability per cell.
+    pub fn set_alive_probability_mask(&mut self, probs: &[f64], seed: u64) -> Result<(), String> {
+        if probs.len() != self.cells.len() {
+            return Err(format!(
+                "expected {} probabilities, got {}",
+                self.cells.len(),
+                probs.len()
+            ));
+        }
+        self.save_snapshot();
+        self.edited();
+        let mut rng = rng::Rng::new(seed);
+        for (cell, &prob) in self.cells.iter_mut().zip(probs.iter()) {
+            *cell = if rng.next_f64() < prob {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.recount();
+        Ok(())
+    }
+    /// Flips each cell independently with probability `rate`, drawing from
+    /// a generator seeded with `seed`. Calling this between ticks models
+    /// random mutations on top of the deterministic rule.
+    pub fn apply_noise(&mut self, rate: f64, seed: u64) {
+        self.save_snapshot();
+        self.edited();
+        let mut rng = rng::Rng::new(seed);
+        for cell in self.cells.iter_mut() {
+            if rng.next_f64() < rate {
+                *cell = cell.toggle();
+            }
+        }
+        self.recount();
+    }
+    /// Kills every cell.
+    pub fn clear(&mut self) {
+        self.save_snapshot();
+        self.edited();
+        self.cells.iter_mut().for_each(|cell| *cell = Cell::Dead);
+        self.live_count = 0;
+    }
+    /// Sets every cell on the outermost ring of the grid alive or dead,
+    /// e.g. to wall off a region of a torus.
+    pub fn set_edge_cells(&mut self, alive: bool) {
+        self.save_snapshot();
+        self.edited();
+        let cell = if alive { Cell::Alive } else { Cell::Dead };
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if row == 0 || col == 0 || row == self.height - 1 || col == self.width - 1 {
+                    let idx = self.get_index(row, col);
+                    self.write_cell(idx, cell);
+                }
+            }
+        }
+    }
+    /// Copies an RLE pattern onto the grid with its top-left corner at
+    /// `(row, col)`, overwriting the cells under its bounding box. Parts of
+    /// the pattern that fall off the grid are dropped. A `rule` in the
+    /// header must be valid but doesn't replace this universe's rule.
+    pub fn stamp_rle(&mut self, rle: &str, row: u32, col: u32) -> Result<(), String> {
+        let pattern = formats::rle::parse(rle)?;
+        self.save_snapshot();
+        self.edited();
+        for r in 0..pattern.height {
+            for c in 0..pattern.width {
+                let (target_r, target_c) = (row.saturating_add(r), col.saturating_add(c));
+                if self.is_within_bounds(target_r, target_c) {
+                    let idx = self.get_index(target_r, target_c);
+                    self.write_cell(idx, Cell::Dead);
+                }
+            }
+        }
+        for &(r, c) in pattern.cells.iter() {
+            let (target_r, target_c) = (row.saturating_add(r), col.saturating_add(c));
+            if self.is_within_bounds(target_r, target_c) {
+                let idx = self.get_index(target_r, target_c);
+                self.write_cell(idx, Cell::Alive);
+            }
+        }
+        Ok(())
+    }
+    /// Stamps the built-in pattern `name` at `(row, col)`, like `stamp_rle`.
+    pub fn insert_pattern(&mut self, name: &str, row: u32, col: u32) -> Result<(), String> {
+        let rle = patterns::rle(name).ok_or_else(|| format!("unknown pattern `{}`", name))?;
+        self.stamp_rle(rle, row, col)
+    }
+    /// Writes `message` in a 3x5 pixel font with its top-left corner at
+    /// `(row, col)`, turning on each glyph's pixels and leaving the cells
+    /// around them alone. Glyphs sit one column apart, and a newline starts
+    /// a new line six rows down. Letters are case-insensitive; besides them
+    /// the font has digits, space and `! ? . , - : '`, and any other
+    /// character is an error. Text running off the grid is clipped.
+    pub fn insert_text(&mut self, message: &str, row: u32, col: u32) -> Result<(), String> {
+        let lines: Vec<Vec<[u8; 5]>> = message
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|ch| font::glyph(ch).ok_or_else(|| format!("no glyph for `{}`", ch)))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        self.save_snapshot();
+        self.edited();
+        for (line_number, glyphs) in lines.iter().enumerate() {
+            let top = u64::from(row) + line_number as u64 * u64::from(font::GLYPH_HEIGHT + 1);
+            for (position, glyph) in glyphs.iter().enumerate() {
+                let left = u64::from(col) + position as u64 * u64::from(font::GLYPH_WIDTH + 1);
+                for (r, bits) in glyph.iter().enumerate() {
+                    for c in 0..font::GLYPH_WIDTH {
+                        let (target_r, target_c) = (top + r as u64, left + u64::from(c));
+                        if bits >> (font::GLYPH_WIDTH - 1 - c) & 1 == 1
+                            && target_r < u64::from(self.height)
+                            && target_c < u64::from(self.width)
+                        {
+                            let idx = self.get_index(target_r as u32, target_c as u32);
+                            self.write_cell(idx, Cell::Alive);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Replaces this universe's grid and generation with a copy of
+    /// `other`'s, reusing the existing allocation where possible. Settings
+    /// such as the rule and boundary are kept. Undoable with `step_back`
+    /// unless the dimensions change.
+    pub fn set_from_other(&mut self, other: &Engine) {
+        if (self.width, self.height) == (other.width, other.height) {
+            self.save_snapshot();
+            self.cells.clone_from(&other.cells);
+        } else {
+            self.width = other.width;
+            self.height = other.height;
+            self.cells.clone_from(&other.cells);
+            self.reset_cell_state();
+        }
+        self.edited();
+        self.live_count = other.live_count;
+        self.generation = other.generation;
+        self.extinct_at = other.extinct_at;
+    }
+    /// A new universe whose cells combine those of `a` and `b` with `op`:
+    /// `"or"`, `"and"`, `"xor"` or `"andnot"` (alive in `a` but not in
+    /// `b`). Both must have the same dimensions; the result has default
+    /// settings.
+    pub fn blend_universes(a: &Engine, b: &Engine, op: &str) -> Result<Engine, String> {
+        let combine: fn(bool, bool) -> bool = match op {
+            "or" => |x, y| x || y,
+            "and" => |x, y| x && y,
+            "xor" => |x, y| x != y,
+            "andnot" => |x, y| x && !y,
+            _ => return Err(format!("unknown blend operation `{}`", op)),
+        };
+        if (a.width, a.height) != (b.width, b.height) {
+            return Err(format!(
+                "cannot blend a {}x{} universe with a {}x{} one",
+                a.width, a.height, b.width, b.height
+            ));
+        }
+        let mut blended = Engine::empty(a.width, a.height);
+        for (idx, (&x, &y)) in a.cells.iter().zip(b.cells.iter()).enumerate() {
+            if combine(x == Cell::Alive, y == Cell::Alive) {
+                blended.write_cell(idx, Cell::Alive);
+            }
+        }
+        Ok(blended)
+    }
+    /// A 64-bit FNV-1a hash of the grid's dimensions and cells. Equal grids
+    /// hash equal regardless of generation or settings.
+    pub fn state_hash(&self) -> u64 {
+        fnv1a(
+            self.width
+                .to_le_bytes()
+                .iter()
+                .chain(self.height.to_le_bytes().iter())
+                .cloned()
+                .chain(self.cells.iter().map(|&cell| cell as u8)),
+        )
+    }
+    /// Ticks until the grid repeats an earlier state, at most `max_steps`
+    /// times, and returns the generation the cycle started at along with
+    /// its period. States are compared by `state_hash`.
+    fn find_cycle(&mut self, max_steps: u32) -> Option<(u32, u32)> {
+        let mut seen = HashMap::new();
+        seen.insert(self.state_hash(), self.generation);
+        for _ in 0..max_steps {
+            self.step();
+            if let Some(onset) = seen.insert(self.state_hash(), self.generation) {
+                return Some((onset, self.generation - onset));
+            }
+        }
+        None
+    }
+    /// Ticks until the grid returns to an earlier state and reports the
+    /// period of the cycle it entered (`1` for a still life), leaving the
+    /// universe where the repeat was found. `None` if no state repeats
+    /// within `max_steps` ticks.
+    pub fn step_and_detect_cycle(&mut self, max_steps: u32) -> Option<u32> {
+        self.find_cycle(max_steps).map(|(_, period)| period)
+    }
+    /// Like `step_and_detect_cycle`, but also returns the generation at
+    /// which the universe first entered the cycle, as `(onset, period)`.
+    pub fn ticks_to_period(&mut self, max_steps: u32) -> Option<(u32, u32)> {
+        self.find_cycle(max_steps)
+    }
+    /// Undoes the most recent tick or edit, returning `false` when there is
+    /// nothing left to undo. Resizing the grid forgets the history.
+    pub fn step_back(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(snapshot) => {
+                self.edited();
+                self.cells.copy_from_slice(&snapshot.cells);
+                self.recount();
+                self.generation = snapshot.generation;
+                true
+            }
+            None => false,
+        }
+    }
+    /// How many states `step_back` can go back through. `0` turns history
+    /// off. Each state keeps a full copy of the grid.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        while self.history.len() > limit {
+            self.history.pop_front();
+        }
+    }
+    /// Advances `n` generations, recording the current grid and each new
+    /// one so a timeline can scrub through them with `snapshot`. Replaces
+    /// any earlier recording and keeps at most the latest 256 generations.
+    pub fn tick_generations_into_history(&mut self, n: u32) {
+        self.recording.clear();
+        self.record_generation();
+        for _ in 0..n {
+            self.step();
+            self.record_generation();
+        }
+    }
+    fn record_generation(&mut self) {
+        self.recording.push_back(Snapshot {
+            cells: self.cells.clone(),
+            generation: self.generation,
+        });
+        while self.recording.len() > RECORDING_LIMIT {
+            self.recording.pop_front();
+        }
+    }
+    /// Number of generations `tick_generations_into_history` recorded.
+    pub fn snapshot_count(&self) -> usize {
+        self.recording.len()
+    }
+    /// A copy of recorded generation `index`, counting from the oldest one
+    /// kept, with this universe's rule and boundary; `None` past the end.
+    pub fn snapshot(&self, index: usize) -> Option<Engine> {
+        let snapshot = self.recording.get(index)?;
+        let mut copy = self.detached_copy(self.rule);
+        copy.cells.clone_from(&snapshot.cells);
+        copy.recount();
+        copy.generation = snapshot.generation;
+        Some(copy)
+    }
+    /// The generations recorded by `tick_generations_into_history` as one
+    /// text, oldest first. Each frame is a complete RLE pattern of its own,
+    /// introduced by a `#C generation <n>` comment line and ended by RLE's
+    /// `!`, so the frames can be split apart at those comment lines. A plain
+    /// RLE reader sees just the first frame.
+    pub fn merge_history_into_rle_animation(&self) -> String {
+        let mut out = String::new();
+        for snapshot in &self.recording {
+            out.push_str(&format!("#C generation {}\n", snapshot.generation));
+            out.push_str(&self.rle_frame(&snapshot.cells));
+        }
+        out
+    }
+    /// Approximate heap bytes held by the universe: the grid, the undo
+    /// history, the activity and age counters, the tick timings, the
+    /// changed-cell list, the population record and the recorded
+    /// generations. Capacity is counted, so this reflects what is allocated
+    /// rather than what is in use.
+    pub fn memory_bytes(&self) -> usize {
+        use std::mem::size_of;
+        let history: usize = self
+            .history
+            .iter()
+            .chain(self.recording.iter())
+            .map(|snapshot| snapshot.cells.capacity() * size_of::<Cell>())
+            .sum();
+        let ages = self.ages.as_ref().map_or(0, |ages| ages.capacity());
+        size_of::<Engine>()
+            + self.cells.capacity() * size_of::<Cell>()
+            + (self.history.capacity() + self.recording.capacity()) * size_of::<Snapshot>()
+            + history
+            + (self.activity.capacity() + ages) * size_of::<u32>()
+            + self.tick_times.capacity() * size_of::<f64>()
+            + self.changed.capacity() * size_of::<u32>()
+            + self.populations.capacity() * size_of::<(u32, u32)>()
+    }
+    /// Moves the whole pattern `delta_r` rows down and `delta_c` columns
+    /// right (negative values move up or left), wrapping cells that cross
+    /// an edge around to the other side.
+    pub fn shift(&mut self, delta_r: i32, delta_c: i32) {
+        if self.cells.is_empty() {
+            return;
+        }
+        self.save_snapshot();
+        self.roll(delta_r as i64, delta_c as i64);
+    }
+    /// When `margin` is non-zero, every `tick` that leaves a live cell within
+    /// `margin` cells of an edge is followed by a `grow(margin)`, so a
+    /// pattern heading for the edge gets room instead of wrapping around or
+    /// dying against it. `0`, the default, turns this off.
+    pub fn auto_resize_on_edge_contact(&mut self, margin: u32) {
+        self.auto_grow_margin = margin;
+    }
+    /// Rotates the grid a quarter turn clockwise, swapping its width and
+    /// height.
+    pub fn rotate_cw(&mut self) {
+        let height = self.height;
+        self.reorient(self.height, self.width, |row, col| (height - 1 - col, row));
+    }
+    /// Rotates the grid a quarter turn counter-clockwise, swapping its width
+    /// and height.
+    pub fn rotate_ccw(&mut self) {
+        let width = self.width;
+        self.reorient(self.height, self.width, |row, col| (col, width - 1 - row));
+    }
+    /// Mirrors the grid across its main diagonal, so rows become columns.
+    pub fn transpose(&mut self) {
+        self.reorient(self.height, self.width, |row, col| (col, row));
+    }
+    /// When on, every `tick` is followed by a `shift` that moves the live
+    /// cells' center of mass back to the middle of the grid. On a torus this
+    /// keeps a travelling pattern in view as if the plane were infinite, as
+    /// long as it stays smaller than the grid. Off by default.
+    pub fn set_recenter(&mut self, enabled: bool) {
+        self.recenter = enabled;
+    }
+    /// The `[row, column]` of the top-left corner of a `target_w` x
+    /// `target_h` viewport that centers the live cells' bounding box, for
+    /// panning a camera. The viewport is kept inside the grid where it fits,
+    /// and an empty grid is framed around its middle.
+    pub fn fit_window(&self, target_w: u32, target_h: u32) -> Vec<u32> {
+        let bounds = self.live_bounds().unwrap_or(Region {
+            top: 0,
+            left: 0,
+            bottom: self.height,
+            right: self.width,
+        });
+        let fit = |start: u32, end: u32, target: u32, size: u32| {
+            let offset = (start as i64 + end as i64 - target as i64) / 2;
+            offset.clamp(0, size.saturating_sub(target) as i64) as u32
+        };
+        vec![
+            fit(bounds.top, bounds.bottom, target_h, self.height),
+            fit(bounds.left, bounds.right, target_w, self.width),
+        ]
+    }
+    /// ORs the mirror image of the live cells across `axis` (`"horizontal"`,
+    /// `"vertical"` or `"both"`) into the grid, making it symmetric.
+    pub fn make_symmetric(&mut self, axis: &str) -> Result<(), String> {
+        let axis = Axis::parse(axis)?;
+        self.save_snapshot();
+        self.edited();
+        if axis != Axis::Vertical {
+            self.or_reflection(false, true);
+        }
+        if axis != Axis::Horizontal {
+            self.or_reflection(true, false);
+        }
+        Ok(())
+    }
+    /// Fills the grid with a random soup that is symmetric across `axis`
+    /// (as for `make_symmetric`): one half, or one quadrant for `"both"`, is
+    /// randomized like `randomize` and mirrored onto the rest.
+    pub fn randomize_symmetric(
+        &mut self,
+        seed: u64,
+        density: f64,
+        axis: &str,
+    ) -> Result<(), String> {
+        let axis = Axis::parse(axis)?;
+        let (mirror_rows, mirror_cols) = (axis != Axis::Horizontal, axis != Axis::Vertical);
+        self.save_snapshot();
+        self.edited();
+        let mut rng = rng::Rng::new(seed);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let src_row = if mirror_rows {
+                    row.min(self.height - 1 - row)
+                } else {
+                    row
+                };
+                let src_col = if mirror_cols {
+                    col.min(self.width - 1 - col)
+                } else {
+                    col
+                };
+                // Mirrored cells come later in row-major order than their
+                // source, which is already filled in.
+                let cell = if (src_row, src_col) != (row, col) {
+                    self.cells[self.get_index(src_row, src_col)]
+                } else if rng.next_f64() < density {
+                    Cell::Alive
+                } else {
+                    Cell::Dead
+                };
+                let idx = self.get_index(row, col);
+                self.write_cell(idx, cell);
+            }
+        }
+        Ok(())
+    }
+    /// Runs two copies of the universe for `steps` generations, one under
+    /// `rule_a` and one under `rule_b` (both in `B3/S23` notation), and
+    /// returns how many cells differ at the end. The universe itself is left
+    /// as it was.
+    pub fn compare_rules(&self, rule_a: &str, rule_b: &str, steps: u32) -> Result<u32, String> {
+        let mut a = self.detached_copy(Rule::parse(rule_a)?);
+        let mut b = self.detached_copy(Rule::parse(rule_b)?);
+        for _ in 0..steps {
+            a.step();
+            b.step();
+        }
+        let differing = a.cells.iter().zip(b.cells.iter()).filter(|(x, y)| x != y);
+        Ok(differing.count() as u32)
+    }
+    /// Sets the rule from the neighbor counts that cause a birth and those
+    /// that let a live cell survive, e.g. `[3]` and `[2, 3]` for Conway's
+    /// Life.
+    pub fn set_rule_from_numbers(&mut self, birth: &[u8], survive: &[u8]) -> Result<(), String> {
+        self.rule = Rule::from_counts(birth, survive)?;
+        self.dirty = None;
+        Ok(())
+    }
+    /// The current rule in `B3/S23` notation.
+    pub fn rule_notation(&self) -> String {
+        self.rule.notation()
+    }
+    /// Whether the grid contains an isolated copy of the built-in pattern
+    /// `name` (e.g. `"block"`, `"blinker"`, `"glider"`) in any orientation.
+    /// The copy must match exactly, with only dead cells in the ring around
+    /// its bounding box, so a block that's part of a larger object doesn't
+    /// count.
+    pub fn contains_pattern(&self, name: &str) -> Result<bool, String> {
+        let rle = patterns::rle(name).ok_or_else(|| format!("unknown pattern `{}`", name))?;
+        let pattern = formats::rle::parse(rle)?;
+        Ok(patterns::orientations(&pattern.cells)
+            .iter()
+            .any(|cells| self.find_isolated(cells)))
+    }
+    /// An estimate of how many spaceships are on the grid. Each group of
+    /// touching live cells is run on its own, under this universe's rule,
+    /// for up to `max_steps` generations; it counts as a spaceship if it
+    /// recurs in its original shape at a different position. Objects that
+    /// never recur within `max_steps`, or that only recur in place (still
+    /// lifes and oscillators), don't count, and neither does a spaceship
+    /// whose cells don't all touch, such as the lightweight spaceship. The
+    /// universe isn't advanced.
+    pub fn detect_spaceships(&self, max_steps: u32) -> u32 {
+        self.components()
+            .iter()
+            .filter(|cells| self.is_spaceship(cells, max_steps))
+            .count() as u32
+    }
+    /// A census of the objects on the grid, meant for a soup that has
+    /// settled: each group of touching live cells is named after the
+    /// catalog pattern it matches (`"block"`, `"beehive"`, `"loaf"`,
+    /// `"boat"`, `"tub"` or `"blinker"`) or reported as `"unknown"`. Objects
+    /// that touch, even diagonally, count as a single unknown one.
+    pub fn classify_still_lifes(&self) -> Vec<String> {
+        self.components()
+            .iter()
+            .map(|cells| patterns::classify(cells).unwrap_or("unknown").to_string())
+            .collect()
+    }
+    pub fn toggle_cell(&mut self, row: u32, cell: u32) {
+        if !self.is_within_bounds(row, cell) {
+            return;
+        }
+        self.edited();
+        let idx = self.get_index(row, cell);
+        self.write_cell(idx, self.cells[idx].toggle());
+    }
+    /// Sets a single cell alive or dead. Out-of-bounds coordinates are
+    /// ignored.
+    pub fn set_cell(&mut self, row: u32, column: u32, alive: bool) {
+        if !self.is_within_bounds(row, column) {
+            return;
+        }
+        self.edited();
+        let idx = self.get_index(row, column);
+        self.write_cell(idx, if alive { Cell::Alive } else { Cell::Dead });
+    }
+    /// Like `set_cell`, but out-of-bounds coordinates are an error.
+    pub fn set_cell_checked(&mut self, row: u32, column: u32, alive: bool) -> Result<(), String> {
+        if !self.is_within_bounds(row, column) {
+            return Err(format!(
+                "cell ({}, {}) is outside the {}x{} grid",
+                row, column, self.width, self.height
+            ));
+        }
+        self.set_cell(row, column, alive);
+        Ok(())
+    }
+    /// Flips every cell.
+    pub fn invert(&mut self) {
+        self.save_snapshot();
+        self.edited();
+        self.cells.iter_mut().for_each(|cell| *cell = cell.toggle());
+        self.live_count = self.cells.len() as u32 - self.live_count;
+    }
+    /// Number of live cells.
+    pub fn population(&self) -> u32 {
+        self.live_count
+    }
+    /// The population at an earlier `generation` (or the current one), or
+    /// `None` if it's in the future or older than the recorded window of
+    /// the latest generations.
+    pub fn population_at(&self, generation: u32) -> Option<u32> {
+        if generation == self.generation {
+            return Some(self.live_count);
+        }
+        if generation > self.generation {
+            return None;
+        }
+        self.populations
+            .iter()
+            .find(|&&(recorded, _)| recorded == generation)
+            .map(|&(_, population)| population)
+    }
+    /// How much the population grew (or, if negative, shrank) on the last
+    /// tick; `0` before the first one.
+    pub fn population_derivative(&self) -> i32 {
+        let mut recent = self.populations.iter().rev();
+        match (recent.next(), recent.next()) {
+            (Some(&(generation, after)), Some(&(_, before))) if generation == self.generation => {
+                after as i32 - before as i32
+            }
+            _ => 0,
+        }
+    }
+    pub fn tick(&mut self) {
+        self.step();
+    }
+    /// Like `tick`, but guaranteed not to move the grid: there is a single
+    /// cell buffer whose contents are overwritten in place, and any
+    /// `auto_resize_on_edge_contact` growth is skipped for this tick.
+    /// Returns the `cells()` pointer, which is the same before and after.
+    pub fn tick_preserving_ptr(&mut self) -> *const Cell {
+        let margin = std::mem::replace(&mut self.auto_grow_margin, 0);
+        self.step();
+        self.auto_grow_margin = margin;
+        self.cells()
+    }
+    /// Like `tick`, but reports whether any cell changed state. A `false`
+    /// result means the universe has reached a still life.
+    pub fn tick_changed(&mut self) -> bool {
+        self.step()
+    }
+    /// Whether the `height` x `width` rectangle at `(row, col)` is locally
+    /// stable: no cell in it would change on the next tick. Only cells whose
+    /// whole neighborhood lies inside the rectangle are checked, so the
+    /// answer doesn't depend on anything outside it. The rectangle is
+    /// clipped to the grid.
+    pub fn region_is_quiescent(&self, row: u32, col: u32, height: u32, width: u32) -> bool {
+        let row_end = row.saturating_add(height).min(self.height);
+        let col_end = col.saturating_add(width).min(self.width);
+        (row.saturating_add(1)..row_end.saturating_sub(1)).all(|r| {
+            (col.saturating_add(1)..col_end.saturating_sub(1)).all(|c| {
+                let cell = self.cells[self.get_index(r, c)];
+                self.rule.next(cell, self.live_neighbor_count(r, c)) == cell
+            })
+        })
+    }
+    /// Like `tick`, but calls `trace(row, column, old, neighbors, new)` for
+    /// every cell, in row-major order, as the rule is applied to it. Much
+    /// slower than `tick`; meant for showing the rule at work.
+    pub fn tick_traced<F: FnMut(u32, u32, Cell, u8, Cell)>(&mut self, mut trace: F) {
+        if self.cells.is_empty() {
+            return;
+        }
+        let mut next = Vec::with_capacity(self.cells.len());
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let cell = self.cells[self.get_index(row, col)];
+                let count = self.live_neighbor_count(row, col);
+                let next_cell = self.rule.next(cell, count);
+                trace(row, col, cell, count, next_cell);
+                next.push(next_cell);
+            }
+        }
+        self.commit(next);
+    }
+    /// Advances one generation, applying the rule to the kernel-weighted
+    /// neighbor sum (see `set_kernel`) instead of the plain neighbor count.
+    /// Sums outside the rule's range leave the cell dead.
+    pub fn tick_weighted(&mut self) {
+        let _timer = Timer::new("Engine::tick_weighted");
+        let rule = self.rule;
+        let sums = self.weighted_sums();
+        self.advance(|idx, cell| match u8::try_from(sums[idx]) {
+            Ok(sum) => rule.next(cell, sum),
+            Err(_) => Cell::Dead,
+        });
+    }
+    /// The kernel-weighted neighbor sum of every cell that `tick_weighted`
+    /// would feed to the rule, in the same layout as `cells()`.
+    pub fn apply_kernel_convolution_debug(&self) -> Vec<i32> {
+        self.weighted_sums()
+    }
+    /// Advances one generation under the isotropic non-totalistic rule in
+    /// Hensel `notation` (e.g. `B2-a/S12`), which looks at which neighbors
+    /// are alive rather than just how many. The universe's own rule is left
+    /// as it is.
+    pub fn tick_isotropic_rule(&mut self, notation: &str) -> Result<(), String> {
+        let rule = IsotropicRule::parse(notation)?;
+        let neighborhoods = self.neighborhoods();
+        self.advance(|idx, cell| rule.next(cell, neighborhoods[idx]));
+        Ok(())
+    }
+    /// Advances `n` generations.
+    pub fn tick_n(&mut self, n: u32) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+    /// Runs up to `max_steps` generations, recording the center of mass of
+    /// the live cells before the first and after each one as a flat
+    /// `[row0, col0, row1, col1, ...]` list. Stops early once the population
+    /// dies out. Wrapping around a torus edge shows up as a jump in the path.
+    pub fn trace_glider_path(&mut self, max_steps: u32) -> Vec<f64> {
+        let mut path = Vec::new();
+        for step in 0..=max_steps {
+            if step > 0 {
+                self.tick();
+            }
+            match self.center_of_mass() {
+                Some((row, col)) => path.extend_from_slice(&[row, col]),
+                None => break,
+            }
+        }
+        path
+    }
+    /// Ticks until the population reaches at least `target`, for at most
+    /// `max_steps` generations. Returns the generation at which it did
+    /// (the current one if it already has), or `None` if it didn't.
+    pub fn run_until_population(&mut self, target: u32, max_steps: u32) -> Option<u32> {
+        for step in 0..=max_steps {
+            if self.live_count >= target {
+                return Some(self.generation);
+            }
+            if step < max_steps {
+                self.tick();
+            }
+        }
+        None
+    }
+    /// Runs as many generations as are due after `elapsed_ms` of wall time;
+    /// see `due_ticks`. Returns the number of generations run.
+    pub fn tick_frame(&mut self, elapsed_ms: f64) -> u32 {
+        let due = self.due_ticks(elapsed_ms);
+        self.tick_n(due);
+        due
+    }
+    /// How many generations are due after `elapsed_ms` of wall time at the
+    /// configured `ticks_per_second`, carrying any remainder over to the
+    /// next call. At most one second's worth of backlog is kept, so a long
+    /// pause (e.g. a hidden tab) doesn't trigger a huge catch-up.
+    pub fn due_ticks(&mut self, elapsed_ms: f64) -> u32 {
+        let interval_ms = 1000.0 / self.ticks_per_second;
+        self.pending_ms = (self.pending_ms + elapsed_ms.max(0.0)).min(1000.0);
+        let due = (self.pending_ms / interval_ms).floor();
+        self.pending_ms -= due * interval_ms;
+        due as u32
+    }
+    pub fn ticks_per_second(&self) -> f64 {
+        self.ticks_per_second
+    }
+    /// The rate is rejected unless it's a positive, finite number.
+    pub fn set_ticks_per_second(&mut self, ticks_per_second: f64) -> Result<(), String> {
+        if !(ticks_per_second.is_finite() && ticks_per_second > 0.0) {
+            return Err("ticks_per_second must be positive".to_string());
+        }
+        self.ticks_per_second = ticks_per_second;
+        Ok(())
+    }
+    /// Sets what lies past the edge of the grid: `"toroidal"` (the default)
+    /// wraps around, `"dead"` treats it as permanently dead.
+    pub fn set_boundary(&mut self, name: &str) -> Result<(), String> {
+        self.boundary = Boundary::parse(name)?;
+        self.dirty = None;
+        Ok(())
+    }
+    /// The current boundary, as accepted by `set_boundary`.
+    pub fn boundary_name(&self) -> String {
+        self.boundary.name().to_string()
+    }
+    /// Makes the torus twisted: a neighbor found by wrapping past the top or
+    /// bottom edge is shifted `delta_c` columns, and one found by wrapping
+    /// past the left or right edge is shifted `delta_r` rows. `(0, 0)`, the
+    /// default, is the standard torus. Has no effect under a dead boundary.
+    pub fn set_torus_offset(&mut self, delta_r: i32, delta_c: i32) {
+        self.torus_offset = (delta_r, delta_c);
+        self.dirty = None;
+    }
+    /// The `[delta_r, delta_c]` set by `set_torus_offset`.
+    pub fn torus_offset(&self) -> Vec<i32> {
+        vec![self.torus_offset.0, self.torus_offset.1]
+    }
+    /// Restores the classic Life configuration: the B3/S23 rule, a toroidal
+    /// boundary without offset and the plain Moore kernel. The cells are
+    /// left as they are.
+    pub fn clear_configuration(&mut self) {
+        self.rule = Rule::conway();
+        self.boundary = Boundary::Toroidal;
+        self.torus_offset = (0, 0);
+        self.kernel = MOORE_KERNEL;
+        self.dirty = None;
+    }
+    /// Total live cells that have died on the edge of a dead-boundary grid
+    /// (see `edge_deaths` for the heuristic).
+    pub fn escaped_count(&self) -> u32 {
+        self.escaped
+    }
+    /// `escaped_count` split by edge: deaths on the top, right, bottom and
+    /// left edges, in that order. A cell dying in a corner counts for both
+    /// of its edges, so the four can add up to more than `escaped_count`.
+    pub fn glider_count_escaped_per_edge(&self) -> Vec<u32> {
+        self.escaped_per_edge.to_vec()
+    }
+    /// Starts recording how long each `tick` takes, keeping the latest
+    /// `limit` durations for `tick_times_ms`. `0` (the default) stops
+    /// recording and discards what was kept.
+    pub fn set_tick_timing(&mut self, limit: usize) {
+        self.tick_times_limit = limit;
+        while self.tick_times.len() > limit {
+            self.tick_times.pop_front();
+        }
+    }
+    /// Milliseconds spent in each of the latest ticks, oldest first; see
+    /// `set_tick_timing`.
+    pub fn tick_times_ms(&self) -> Vec<f64> {
+        self.tick_times.iter().cloned().collect()
+    }
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+    /// Sets the generation counter, e.g. to resume a saved session's
+    /// numbering. The cells are left alone; the population record and the
+    /// extinction generation, which are tied to the old numbering, start
+    /// over.
+    pub fn set_generation(&mut self, generation: u32) {
+        self.generation = generation;
+        self.extinct_at = None;
+        self.populations.clear();
+        self.record_population();
+    }
+    /// The generation at which the population first dropped to zero, if it
+    /// has. Editing or resizing the grid starts the count over.
+    pub fn generation_at_extinction(&self) -> Option<u32> {
+        self.extinct_at
+    }
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+    /// The grid as text with `alive` and `dead` for the cells and rows
+    /// separated by newlines. The last row ends in a newline only when
+    /// `trailing_newline` is set; `render` is `to_ascii_grid('◼', '◻', true)`.
+    pub fn to_ascii_grid(&self, alive: char, dead: char, trailing_newline: bool) -> String {
+        let mut out = String::new();
+        for (row, line) in self.cells.chunks(self.width.max(1) as usize).enumerate() {
+            if row > 0 {
+                out.push('\n');
+            }
+            out.extend(
+                line.iter()
+                    .map(|&cell| if cell == Cell::Alive { alive } else { dead }),
+            );
+        }
+        if trailing_newline && !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+    /// A grid comparing this universe with `other`, one line per row: `+`
+    /// marks a cell alive only here, `-` one alive only in `other` and `=`
+    /// a cell on which they agree. Grids of different sizes are compared
+    /// over the larger of each dimension, cells past an edge counting as
+    /// dead.
+    pub fn diff_rle(&self, other: &Engine) -> String {
+        let alive = |engine: &Engine, row: u32, col: u32| {
+            engine.is_within_bounds(row, col)
+                && engine.cells[engine.get_index(row, col)] == Cell::Alive
+        };
+        let mut out = String::new();
+        for row in 0..self.height.max(other.height) {
+            for col in 0..self.width.max(other.width) {
+                out.push(match (alive(self, row, col), alive(other, row, col)) {
+                    (true, false) => '+',
+                    (false, true) => '-',
+                    _ => '=',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+    /// The live cells in the Life 1.05 format: a `#N` line for Conway's rule
+    /// or `#R` with the rule in survival/birth form, then a `#P x y` block
+    /// of `*` and `.` rows for each band of rows with live cells in them.
+    /// Block positions are relative to the middle of the grid, and rows
+    /// wider than 80 cells are split across blocks.
+    pub fn to_lif_105(&self) -> String {
+        let mut out = String::from("#Life 1.05\n");
+        if self.rule == Rule::conway() {
+            out.push_str("#N\n");
+        } else {
+            out.push_str(&format!("#R {}\n", self.rule.survival_birth_notation()));
+        }
+        let alive = |cell: &Cell| *cell == Cell::Alive;
+        let rows: Vec<&[Cell]> = self.cells.chunks(self.width.max(1) as usize).collect();
+        let mut end = 0;
+        while end < rows.len() {
+            if !rows[end].iter().any(alive) {
+                end += 1;
+                continue;
+            }
+            let start = end;
+            while end < rows.len() && rows[end].iter().any(alive) {
+                end += 1;
+            }
+            let band = &rows[start..end];
+            let left = band
+                .iter()
+                .filter_map(|row| row.iter().position(alive))
+                .min();
+            let right = band
+                .iter()
+                .filter_map(|row| row.iter().rposition(alive))
+                .max();
+            let (left, right) = (left.unwrap_or(0), right.map_or(0, |right| right + 1));
+            for block_left in (left..right).step_by(LIFE_105_LINE_WIDTH) {
+                let block_right = (block_left + LIFE_105_LINE_WIDTH).min(right);
+                let lines: Vec<&[Cell]> = band
+                    .iter()
+                    .map(|row| &row[block_left..block_right])
+                    .collect();
+                if !lines.iter().any(|line| line.iter().any(alive)) {
+                    continue;
+                }
+                out.push_str(&format!(
+                    "#P {} {}\n",
+                    block_left as i64 - i64::from(self.width / 2),
+                    start as i64 - i64::from(self.height / 2)
+                ));
+                for line in lines {
+                    let used = line.iter().rposition(alive).map_or(1, |last| last + 1);
+                    out.extend(line[..used].iter().map(|&cell| {
+                        if cell == Cell::Alive {
+                            '*'
+                        } else {
+                            '.'
+                        }
+                    }));
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+    /// The grid as comma-separated rows of `1` (alive) and `0` (dead), one
+    /// line per row, for loading into a spreadsheet; see `from_csv`.
+    pub fn export_csv(&self) -> String {
+        let mut out = String::new();
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            let fields: Vec<&str> = line
+                .iter()
+                .map(|&cell| if cell == Cell::Alive { "1" } else { "0" })
+                .collect();
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+        out
+    }
+    /// Like `render`, but packs each 2x4 block of cells into one braille
+    /// character, giving a grid four times shorter and half as wide. Blocks
+    /// hanging over the right or bottom edge treat the missing cells as dead.
+    pub fn render_braille(&self) -> String {
+        // Dot bit for each (row, column) offset within a block.
+        const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        let mut out = String::new();
+        for top in (0..self.height).step_by(4) {
+            for left in (0..self.width).step_by(2) {
+                let mut bits = 0;
+                for (dr, dots) in DOTS.iter().enumerate() {
+                    for (dc, &dot) in dots.iter().enumerate() {
+                        let (row, col) = (top + dr as u32, left + dc as u32);
+                        if self.is_within_bounds(row, col)
+                            && self.cells[self.get_index(row, col)] == Cell::Alive
+                        {
+                            bits |= dot;
+                        }
+                    }
+                }
+                out.push(std::char::from_u32(0x2800 + bits).unwrap());
+            }
+            out.push('\n');
+        }
+        out
+    }
+    /// Flat indices (as laid out by `cells()`) of the cells the last tick
+    /// changed, ascending. Edits made since then aren't included.
+    pub fn changed_cells(&self) -> Vec<u32> {
+        self.changed.clone()
+    }
+    /// Flat indices (as laid out by `cells()`) of the cells that differ from
+    /// `snapshot`, ascending, e.g. to highlight what changed since a saved
+    /// state. The two grids must be the same size.
+    pub fn highlight_changed_since(&self, snapshot: &Engine) -> Result<Vec<u32>, String> {
+        if (self.width, self.height) != (snapshot.width, snapshot.height) {
+            return Err(format!(
+                "snapshot is {}x{}, expected {}x{}",
+                snapshot.width, snapshot.height, self.width, self.height
+            ));
+        }
+        Ok(self
+            .cells
+            .iter()
+            .zip(snapshot.cells.iter())
+            .enumerate()
+            .filter(|(_, (now, then))| now != then)
+            .map(|(idx, _)| idx as u32)
+            .collect())
+    }
+    /// `[births, deaths]` of the last tick: the cells in `changed_cells`
+    /// that are now alive and now dead respectively.
+    pub fn count_births_deaths_last_tick(&self) -> Vec<u32> {
+        let births = self
+            .changed
+            .iter()
+            .filter(|&&idx| self.cells[idx as usize] == Cell::Alive)
+            .count() as u32;
+        vec![births, self.changed.len() as u32 - births]
+    }
+    /// The cells in `changed_cells` as `row, column, color` triples laid out
+    /// back to back, each colored `alive` or `dead` (`0xRRGGBBAA`) by its
+    /// current state. Redrawing just these after a tick is much cheaper than
+    /// a full `render_rgba` on a sparse grid.
+    pub fn render_patches(&self, alive: u32, dead: u32) -> Vec<u32> {
+        let mut patches = Vec::with_capacity(self.changed.len() * 3);
+        for &idx in self.changed.iter() {
+            let color = if self.cells[idx as usize] == Cell::Alive {
+                alive
+            } else {
+                dead
+            };
+            patches.extend_from_slice(&[idx / self.width, idx % self.width, color]);
+        }
+        patches
+    }
+    /// One RGBA pixel per cell, row-major, suitable for `ImageData`. Colors
+    /// are given as `0xRRGGBBAA`.
+    pub fn render_rgba(&self, alive: u32, dead: u32) -> Vec<u8> {
+        let alive = alive.to_be_bytes();
+        let dead = dead.to_be_bytes();
+        let mut pixels = Vec::with_capacity(self.cells.len() * 4);
+        for &cell in self.cells.iter() {
+            let color = if cell == Cell::Alive { &alive } else { &dead };
+            pixels.extend_from_slice(color);
+        }
+        pixels
+    }
+    /// Like `render_rgba`, but each cell is a `cell_px` x `cell_px` block of
+    /// pixels, giving an image `width * cell_px` pixels wide and `height *
+    /// cell_px` tall. Drawing this at its natural size zooms in without the
+    /// blur of scaling up the canvas. An image with more pixels than the
+    /// `enforce_max_dimensions` cell limit is an error.
+    pub fn render_rgba_scaled(
+        &self,
+        cell_px: u32,
+        alive: u32,
+        dead: u32,
+    ) -> Result<Vec<u8>, String> {
+        let image_pixels = (self.cells.len() as u64)
+            .checked_mul(u64::from(cell_px) * u64::from(cell_px))
+            .filter(|&pixels| pixels <= formats::max_cells())
+            .ok_or_else(|| {
+                format!(
+                    "a {}x{} grid at {} pixels per cell exceeds the limit of {} pixels",
+                    self.width,
+                    self.height,
+                    cell_px,
+                    formats::max_cells()
+                )
+            })?;
+        let alive = alive.to_be_bytes();
+        let dead = dead.to_be_bytes();
+        let cell_px = cell_px as usize;
+        let mut pixels = Vec::with_capacity(image_pixels as usize * 4);
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            let start = pixels.len();
+            for &cell in line {
+                let color = if cell == Cell::Alive { &alive } else { &dead };
+                for _ in 0..cell_px {
+                    pixels.extend_from_slice(color);
+                }
+            }
+            // The remaining pixel rows of this cell row repeat the first.
+            let end = pixels.len();
+            for _ in 1..cell_px {
+                pixels.extend_from_within(start..end);
+            }
+        }
+        Ok(pixels)
+    }
+    /// Advances `count` generations and returns the `render_rgba` buffer of
+    /// each new generation back to back. Frame `i` starts at byte
+    /// `i * width * height * 4`.
+    pub fn capture_frames(&mut self, count: u32, alive: u32, dead: u32) -> Vec<u8> {
+        let mut frames = Vec::with_capacity(count as usize * self.cells.len() * 4);
+        for _ in 0..count {
+            self.tick();
+            frames.extend(self.render_rgba(alive, dead));
+        }
+        frames
+    }
+    /// An SVG image of the grid with one `cell_size` square `<rect>` per
+    /// live cell filled with the CSS color `alive`. When `dead` is set, a
+    /// white background rect is drawn first. Coordinates are computed in 64
+    /// bits, so no `cell_size` overflows them.
+    pub fn to_svg(&self, cell_size: u32, alive: &str, dead: bool) -> String {
+        let cell_size = u64::from(cell_size);
+        let (width, height) = (
+            u64::from(self.width) * cell_size,
+            u64::from(self.height) * cell_size,
+        );
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = width,
+            h = height
+        );
+        if dead {
+            svg.push_str(&format!(
+                "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+                width, height
+            ));
+        }
+        let fill = escape_xml(alive);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Alive {
+                    svg.push_str(&format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"{}\"/>\n",
+                        u64::from(col) * cell_size,
+                        u64::from(row) * cell_size,
+                        fill,
+                        s = cell_size
+                    ));
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+    /// Turns per-cell age tracking on or off. While on, each `tick` records
+    /// how many generations every cell has spent in its current state,
+    /// which `render_rgba_with_trails` uses.
+    pub fn set_track_ages(&mut self, enabled: bool) {
+        self.ages = if enabled {
+            Some(self.initial_ages())
+        } else {
+            None
+        };
+    }
+    /// Generations each cell has been in its current state, `0` meaning it
+    /// changed on the last tick. Empty unless age tracking is on.
+    pub fn ages(&self) -> Vec<u32> {
+        self.ages.clone().unwrap_or_default()
+    }
+    /// Like `render_rgba`, but cells that died within the last `trail_len`
+    /// generations are drawn partway between `alive` and `dead`, fading out
+    /// as they stay dead. Needs age tracking (`set_track_ages`); without it
+    /// this draws no trails.
+    pub fn render_rgba_with_trails(&self, alive: u32, dead: u32, trail_len: u32) -> Vec<u8> {
+        let ages = match &self.ages {
+            Some(ages) if trail_len > 0 => ages,
+            _ => return self.render_rgba(alive, dead),
+        };
+        let alive_rgba = alive.to_be_bytes();
+        let dead_rgba = dead.to_be_bytes();
+        let mut pixels = Vec::with_capacity(self.cells.len() * 4);
+        for (&cell, &age) in self.cells.iter().zip(ages.iter()) {
+            if cell == Cell::Alive {
+                pixels.extend_from_slice(&alive_rgba);
+            } else if age < trail_len {
+                // Fraction of the way from the alive to the dead color.
+                let t = (age + 1) as f64 / (trail_len + 1) as f64;
+                for channel in 0..4 {
+                    let from = alive_rgba[channel] as f64;
+                    let to = dead_rgba[channel] as f64;
+                    pixels.push((from + (to - from) * t).round() as u8);
+                }
+            } else {
+                pixels.extend_from_slice(&dead_rgba);
+            }
+        }
+        pixels
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    /// Pointer to the grid in wasm memory, one byte per cell, row-major.
+    ///
+    /// The pointer stays valid across ticks and edits but not across a
+    /// resize (including the one a `tick` makes under
+    /// `auto_resize_on_edge_contact`), which moves the grid and bumps
+    /// `cells_version`; `tick_preserving_ptr` never resizes. JS that keeps
+    /// a view over this memory should compare `cells_version` with the one
+    /// it saw when taking the pointer and re-acquire it when they differ.
+    /// (Views also have to be recreated if the wasm memory grows.)
+    pub fn cells(&self) -> *const Cell {
+        self.cells.as_ptr()
+    }
+    /// A copy of the grid as one byte per cell, `1` for alive and `0` for
+    /// dead. Unlike `cells()` this stays valid after the universe changes.
+    pub fn as_bool_vec(&self) -> Vec<u8> {
+        self.cells.iter().map(|&cell| cell as u8).collect()
+    }
+    /// The grid packed one bit per cell, each row padded to whole `u32`
+    /// words (`width.div_ceil(32)` per row) and rows laid out top to bottom.
+    /// Column `c` of a row is bit `c % 32` of its word `c / 32`, counting
+    /// from the least significant bit; padding bits are zero.
+    pub fn to_packed_u32_rows(&self) -> Vec<u32> {
+        let words_per_row = self.width.div_ceil(32) as usize;
+        let mut words = vec![0; words_per_row * self.height as usize];
+        for (idx, _) in self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == Cell::Alive)
+        {
+            let (row, col) = (idx / self.width as usize, idx % self.width as usize);
+            words[row * words_per_row + col / 32] |= 1 << (col % 32);
+        }
+        words
+    }
+    /// The grid as run lengths, row by row, top to bottom. Each row is a
+    /// count `n` followed by `n` run lengths that alternate between dead and
+    /// alive cells, starting with dead (so a row that starts alive begins
+    /// with a `0`). Dead cells after the last live run aren't listed, which
+    /// leaves an empty row as just `0`.
+    pub fn as_run_length_rows(&self) -> Vec<u32> {
+        let mut out = Vec::new();
+        for line in self.cells.chunks(self.width.max(1) as usize) {
+            let prefix = out.len();
+            out.push(0);
+            let used = line
+                .iter()
+                .rposition(|&cell| cell == Cell::Alive)
+                .map_or(0, |last| last + 1);
+            let mut expected = Cell::Dead;
+            let mut run = 0;
+            for &cell in &line[..used] {
+                if cell != expected {
+                    out.push(run);
+                    expected = cell;
+                    run = 0;
+                }
+                run += 1;
+            }
+            if used > 0 {
+                out.push(run);
+            }
+            out[prefix] = (out.len() - prefix - 1) as u32;
+        }
+        out
+    }
+    /// Flat indices (as laid out by `cells()`) of the live cells, ascending.
+    pub fn alive_indices(&self) -> Vec<u32> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == Cell::Alive)
+            .map(|(idx, _)| idx as u32)
+            .collect()
+    }
+    /// Live cell counts of the top-left, top-right, bottom-left and
+    /// bottom-right quadrants. The grid is split at `height / 2` and
+    /// `width / 2`, so with an odd dimension the middle row or column falls
+    /// in the bottom or right quadrants.
+    pub fn quadrant_populations(&self) -> Vec<u32> {
+        let mut counts = vec![0; 4];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Alive {
+                    let bottom = (row >= self.height / 2) as usize;
+                    let right = (col >= self.width / 2) as usize;
+                    counts[bottom * 2 + right] += 1;
+                }
+            }
+        }
+        counts
+    }
+    /// The `[row, column]` of flat `index`, the inverse of `get_index`.
+    /// Empty when the index is past the end of the grid.
+    pub fn row_col_of_index(&self, index: usize) -> Vec<u32> {
+        if index >= self.cells.len() {
+            return Vec::new();
+        }
+        let index = index as u32;
+        vec![index / self.width, index % self.width]
+    }
+    /// Whether `(row, column)` names a cell of the grid.
+    pub fn is_within_bounds(&self, row: u32, column: u32) -> bool {
+        row < self.height && column < self.width
+    }
+    /// Whether any neighbor of `(row, column)` is reached by wrapping around
+    /// the grid: true for the cells on the edge of a toroidal grid, false
+    /// in the interior, under a dead boundary and outside the grid.
+    pub fn neighbors_wrapped_flag(&self, row: u32, column: u32) -> bool {
+        self.boundary == Boundary::Toroidal
+            && self.is_within_bounds(row, column)
+            && (row == 0 || column == 0 || row + 1 == self.height || column + 1 == self.width)
+    }
+    /// Whether the cell at flat `index` (as laid out by `cells()`) is alive.
+    /// Indices past the end of the grid read as dead.
+    pub fn bit_at(&self, index: usize) -> bool {
+        self.cells.get(index) == Some(&Cell::Alive)
+    }
+    /// How many times each cell has changed state during `tick`, in the same
+    /// layout as `cells()`. Cleared by `reset_activity` and on resize.
+    pub fn activity(&self) -> Vec<u32> {
+        self.activity.clone()
+    }
+    pub fn reset_activity(&mut self) {
+        self.activity.iter_mut().for_each(|count| *count = 0);
+    }
+    /// Replaces the whole grid from one byte per cell in the layout of
+    /// `cells()`, nonzero meaning alive; the inverse of `as_bool_vec`. Use
+    /// this for bulk writes such as importing an image rather than writing
+    /// through the `cells()` pointer, which would bypass the population
+    /// count and undo history. Errors unless `data` has exactly
+    /// `width * height` bytes.
+    pub fn write_cells(&mut self, data: &[u8]) -> Result<(), String> {
+        if data.len() != self.cells.len() {
+            return Err(format!(
+                "expected {} bytes, got {}",
+                self.cells.len(),
+                data.len()
+            ));
+        }
+        self.save_snapshot();
+        self.edited();
+        for (cell, &byte) in self.cells.iter_mut().zip(data.iter()) {
+            *cell = if byte != 0 { Cell::Alive } else { Cell::Dead };
+        }
+        self.recount();
+        Ok(())
+    }
+    /// Changes whenever the grid is reallocated, invalidating `cells()`.
+    pub fn cells_version(&self) -> u32 {
+        self.cells_version
+    }
+    /// Adds `margin` dead rows and columns on every side, keeping the live
+    /// cells, their activity counts and ages where they are relative to
+    /// each other. The undo history and recorded generations, which hold
+    /// grids of the old size, are dropped. Does nothing if the grid would
+    /// exceed the cell limit.
+    pub fn grow(&mut self, margin: u32) {
+        let width = u64::from(self.width) + 2 * u64::from(margin);
+        let height = u64::from(self.height) + 2 * u64::from(margin);
+        if margin == 0 || width * height > formats::max_cells() {
+            return;
+        }
+        self.cells = self.padded(&self.cells, margin, Cell::Dead);
+        self.activity = self.padded(&self.activity, margin, 0);
+        if let Some(ages) = &self.ages {
+            self.ages = Some(self.padded(ages, margin, u32::MAX));
+        }
+        self.width = width as u32;
+        self.height = height as u32;
+        self.dirty = None;
+        self.cells_version = self.cells_version.wrapping_add(1);
+        self.changed.clear();
+        self.history.clear();
+        self.recording.clear();
+    }
+    /// Caps the number of cells a universe may have at `limit`: pattern
+    /// loaders, `load`, `from_image_data`, `resize`, `soup_search`,
+    /// `simulate_to_json` and `CrittersUniverse::new` report an error past
+    /// it, and `set_width`, `set_height` and `grow` do nothing. Guards
+    /// against untrusted input exhausting memory; the default is 16M cells,
+    /// and cell indices are 32-bit, so `limit` can't exceed `u32::MAX`.
+    pub fn enforce_max_dimensions(limit: u64) -> Result<(), String> {
+        if limit == 0 || limit > u64::from(u32::MAX) {
+            return Err(format!("the cell limit must be between 1 and {}", u32::MAX));
+        }
+        formats::set_max_cells(limit);
+        Ok(())
+    }
+    /// Resizes the grid to `width` x `height`, clearing it. A grid larger
+    /// than the `enforce_max_dimensions` limit is an error and leaves the
+    /// universe as it was.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
+        check_dimensions(width, height)?;
+        self.width = width;
+        self.height = height;
+        self.cells = vec![Cell::Dead; (width * height) as usize];
+        self.reset_cell_state();
+        Ok(())
+    }
+    /// Like `resize`, keeping the height; a width over the cell limit is
+    /// ignored.
+    pub fn set_width(&mut self, width: u32) {
+        let _ = self.resize(width, self.height);
+    }
+    /// Like `resize`, keeping the width; a height over the cell limit is
+    /// ignored.
+    pub fn set_height(&mut self, height: u32) {
+        let _ = self.resize(self.width, height);
+    }
+}
+
+/// Checks a `width` x `height` grid against the `enforce_max_dimensions`
+/// limit.
+pub(crate) fn check_dimensions(width: u32, height: u32) -> Result<(), String> {
+    let cells = u64::from(width) * u64::from(height);
+    if cells > formats::max_cells() {
+        return Err(format!(
+            "{}x{} grid exceeds the limit of {} cells",
+            width,
+            height,
+            formats::max_cells()
+        ));
+    }
+    Ok(())
+}
+
+/// 64-bit FNV-1a hash of `bytes`.
+fn fnv1a<I: IntoIterator<Item = u8>>(bytes: I) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.into_iter().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.cells.is_empty() {
+            return Ok(());
+        }
+        for line in self.cells.as_slice().chunks(self.width as usize) {
+            for &cell in line {
+                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
+                write!(f, "{}", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Comma-separated `0`/`1` grids, one grid row per line, for spreadsheets
+//! and data frames. Every row must have the same number of cells.
+
+use super::{max_cells, Pattern};
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let rows: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if rows.is_empty() {
+        return Err("pattern has no rows".to_string());
+    }
+    let mut cells = Vec::new();
+    let mut width = None;
+    for (row, line) in rows.iter().enumerate() {
+        let mut len = 0u64;
+        for (col, field) in line.split(',').enumerate() {
+            match field.trim() {
+                "1" => cells.push((row as u32, col as u32)),
+                "0" => {}
+                other => {
+                    return Err(format!(
+                        "expected `0` or `1` on row {}, found `{}`",
+                        row + 1,
+                        other
+                    ))
+                }
+            }
+            len += 1;
+        }
+        match width {
+            None if len * rows.len() as u64 > max_cells() => {
+                return Err(format!(
+                    "pattern exceeds the limit of {} cells",
+                    max_cells()
+                ));
+            }
+            None => width = Some(len),
+            Some(width) if width != len => {
+                return Err(format!(
+                    "row {} has {} cells, expected {}",
+                    row + 1,
+                    len,
+                    width
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(Pattern {
+        width: width.unwrap_or(0) as u32,
+        height: rows.len() as u32,
+        cells,
+        rule: None,
+    })
+}
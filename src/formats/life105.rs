@@ -0,0 +1,52 @@
+//! The Life 1.05 format: a `#Life 1.05` line followed by blocks of `.`
+//! (dead) and `*` (alive) rows, each introduced by a `#P x y` line giving
+//! the position of its top-left cell.
+//!
+//! Blocks may overlap, in which case their live cells are combined. As in
+//! Life 1.06, coordinates may be negative, so the pattern is shifted to have
+//! its bounding box start at the origin. A `#R survival/birth` line, e.g.
+//! `#R 23/36`, sets the rule; `#N` or no rule line means Conway's Life.
+
+use super::{from_points, parse_coordinate, Pattern};
+use crate::rule::Rule;
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    if lines.next() != Some("#Life 1.05") {
+        return Err("missing `#Life 1.05` header".to_string());
+    }
+    let mut points = Vec::new();
+    let mut rule = None;
+    let mut block: Option<(i64, i64)> = None;
+    let mut row = 0;
+    for line in lines {
+        if let Some(position) = line.strip_prefix("#P") {
+            let mut fields = position.split_whitespace();
+            block = match (fields.next(), fields.next(), fields.next()) {
+                (Some(x), Some(y), None) => Some((parse_coordinate(x)?, parse_coordinate(y)?)),
+                _ => return Err(format!("expected `#P x y`, found `{}`", line)),
+            };
+            row = 0;
+            continue;
+        }
+        if let Some(notation) = line.strip_prefix("#R") {
+            rule = Some(Rule::parse(notation)?);
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let (x, y) = block.ok_or("cell rows must follow a `#P x y` line")?;
+        for (col, ch) in line.chars().enumerate() {
+            match ch {
+                '*' => points.push((x + col as i64, y + row)),
+                '.' => {}
+                c => return Err(format!("unexpected character `{}` in pattern", c)),
+            }
+        }
+        row += 1;
+    }
+    let mut pattern = from_points(&points)?;
+    pattern.rule = rule;
+    Ok(pattern)
+}
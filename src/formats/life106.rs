@@ -0,0 +1,27 @@
+//! The Life 1.06 format: a `#Life 1.06` line followed by one `x y`
+//! coordinate pair per live cell.
+//!
+//! Coordinates may be negative, so the pattern is shifted to have its
+//! bounding box start at the origin.
+
+use super::{from_points, parse_coordinate, Pattern};
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    if lines.next() != Some("#Life 1.06") {
+        return Err("missing `#Life 1.06` header".to_string());
+    }
+    let mut points = Vec::new();
+    for line in lines {
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let (x, y) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(x), Some(y), None) => (parse_coordinate(x)?, parse_coordinate(y)?),
+            _ => return Err(format!("expected an `x y` pair, found `{}`", line)),
+        };
+        points.push((x, y));
+    }
+    from_points(&points)
+}
@@ -0,0 +1,108 @@
+//! Loaders for the common Life pattern file formats.
+//!
+//! Input is treated as untrusted: every malformed pattern is reported as an
+//! error instead of panicking, and declared sizes are capped by `max_cells`.
+
+pub mod csv;
+pub mod life105;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+use std::cell::Cell;
+
+use crate::rule::Rule;
+
+/// Default for `max_cells`.
+pub const DEFAULT_MAX_CELLS: u64 = 1 << 24;
+
+thread_local! {
+    static MAX_CELLS: Cell<u64> = const { Cell::new(DEFAULT_MAX_CELLS) };
+}
+
+/// Largest grid (in cells) a pattern file is allowed to declare or a
+/// universe is allowed to grow to. The limit is per thread; a wasm module
+/// only has the one.
+pub fn max_cells() -> u64 {
+    MAX_CELLS.with(Cell::get)
+}
+
+pub fn set_max_cells(limit: u64) {
+    MAX_CELLS.with(|max| max.set(limit));
+}
+
+pub struct Pattern {
+    pub width: u32,
+    pub height: u32,
+    /// Live cells as `(row, column)` pairs.
+    pub cells: Vec<(u32, u32)>,
+    /// The rule the file declares, for formats that can carry one.
+    pub rule: Option<Rule>,
+}
+
+/// Guesses the format of `text` and parses it with the matching loader.
+pub fn parse_any(text: &str) -> Result<Pattern, String> {
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .ok_or("pattern is empty")?;
+    if first == "#Life 1.05" {
+        life105::parse(text)
+    } else if first == "#Life 1.06" {
+        life106::parse(text)
+    } else if first.starts_with('#') || is_rle_header(first) {
+        rle::parse(text)
+    } else if first.starts_with('!') || plaintext::is_grid_line(first) {
+        plaintext::parse(text)
+    } else {
+        Err(
+            "unrecognized pattern format; expected RLE, Life 1.05, Life 1.06 or plain text"
+                .to_string(),
+        )
+    }
+}
+
+/// A pattern holding the live cells at `(x, y)` in `points`, shifted so its
+/// bounding box starts at the origin.
+fn from_points(points: &[(i64, i64)]) -> Result<Pattern, String> {
+    if points.is_empty() {
+        return Err("pattern has no live cells".to_string());
+    }
+    let min_x = points.iter().map(|p| p.0).min().unwrap();
+    let max_x = points.iter().map(|p| p.0).max().unwrap();
+    let min_y = points.iter().map(|p| p.1).min().unwrap();
+    let max_y = points.iter().map(|p| p.1).max().unwrap();
+    let width = (max_x - min_x + 1) as u64;
+    let height = (max_y - min_y + 1) as u64;
+    if width * height > max_cells() {
+        return Err(format!(
+            "pattern of {}x{} exceeds the limit of {} cells",
+            width,
+            height,
+            max_cells()
+        ));
+    }
+    let cells = points
+        .iter()
+        .map(|&(x, y)| ((y - min_y) as u32, (x - min_x) as u32))
+        .collect();
+    Ok(Pattern {
+        width: width as u32,
+        height: height as u32,
+        cells,
+        rule: None,
+    })
+}
+
+fn parse_coordinate(value: &str) -> Result<i64, String> {
+    value
+        .parse::<i32>()
+        .map(i64::from)
+        .map_err(|_| format!("invalid coordinate `{}`", value))
+}
+
+fn is_rle_header(line: &str) -> bool {
+    line.strip_prefix('x')
+        .is_some_and(|rest| rest.trim_start().starts_with('='))
+}
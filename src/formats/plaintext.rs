@@ -0,0 +1,64 @@
+//! Plain text grids: one line per row, with `O` or `*` for live cells and
+//! `.` or spaces for dead ones. Lines starting with `!` are comments, which
+//! makes this a superset of the LifeWiki `.cells` format. The `◼`/`◻`
+//! glyphs written by `Universe::render` are accepted too.
+//!
+//! Ragged lines are padded with dead cells up to the widest line.
+
+use super::{max_cells, Pattern};
+
+pub fn is_grid_line(line: &str) -> bool {
+    line.chars().all(|ch| is_alive(ch) || is_dead(ch))
+}
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let rows: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.starts_with('!'))
+        .collect();
+    // Blank lines after the grid aren't rows; blank lines inside it are.
+    let end = rows
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .ok_or("pattern has no rows")?;
+    let rows = &rows[..=end];
+
+    let mut cells = Vec::new();
+    let mut width = 0u64;
+    for (row, line) in rows.iter().enumerate() {
+        let mut len = 0u64;
+        for (col, ch) in line.chars().enumerate() {
+            if is_alive(ch) {
+                cells.push((row as u32, col as u32));
+            } else if !is_dead(ch) {
+                return Err(format!("unexpected character `{}` on row {}", ch, row + 1));
+            }
+            len += 1;
+        }
+        width = width.max(len);
+        if width * rows.len() as u64 > max_cells() {
+            return Err(format!(
+                "pattern exceeds the limit of {} cells",
+                max_cells()
+            ));
+        }
+    }
+    if width == 0 {
+        return Err("pattern has no columns".to_string());
+    }
+    Ok(Pattern {
+        width: width as u32,
+        height: rows.len() as u32,
+        cells,
+        rule: None,
+    })
+}
+
+fn is_alive(ch: char) -> bool {
+    matches!(ch, 'O' | '*' | '◼')
+}
+
+fn is_dead(ch: char) -> bool {
+    matches!(ch, '.' | ' ' | '◻')
+}
@@ -0,0 +1,112 @@
+//! The run length encoded (RLE) pattern format.
+//!
+//! See https://conwaylife.com/wiki/Run_Length_Encoded for the format.
+
+use super::{max_cells, Pattern};
+use crate::rule::Rule;
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let header = loop {
+        match lines.next() {
+            Some(line) if line.starts_with('#') => continue,
+            Some(line) => break line,
+            None => return Err("missing `x = .., y = ..` header".to_string()),
+        }
+    };
+    let (width, height, rule) = parse_header(header)?;
+
+    let mut cells = Vec::new();
+    let (mut row, mut col) = (0u32, 0u32);
+    let mut run: Option<u32> = None;
+    for line in lines {
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => {
+                    let digit = ch.to_digit(10).unwrap();
+                    let count = run
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit))
+                        .ok_or("run count is too large")?;
+                    run = Some(count);
+                }
+                'b' | 'o' => {
+                    let count = run.take().unwrap_or(1);
+                    let end = col
+                        .checked_add(count)
+                        .filter(|&end| end <= width)
+                        .ok_or("pattern row is wider than the declared width")?;
+                    if ch == 'o' {
+                        if row >= height {
+                            return Err("pattern is taller than the declared height".to_string());
+                        }
+                        cells.extend((col..end).map(|c| (row, c)));
+                    }
+                    col = end;
+                }
+                '$' => {
+                    let count = run.take().unwrap_or(1);
+                    row = row
+                        .checked_add(count)
+                        .filter(|&row| row <= height)
+                        .ok_or("pattern is taller than the declared height")?;
+                    col = 0;
+                }
+                '!' => {
+                    if run.is_some() {
+                        return Err("run count is not followed by a tag".to_string());
+                    }
+                    return Ok(Pattern {
+                        width,
+                        height,
+                        cells,
+                        rule,
+                    });
+                }
+                c if c.is_whitespace() => {}
+                c => return Err(format!("unexpected character `{}` in pattern", c)),
+            }
+        }
+    }
+    Err("pattern is missing its terminating `!`".to_string())
+}
+
+fn parse_header(header: &str) -> Result<(u32, u32, Option<Rule>), String> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+    for field in header.split(',') {
+        let mut parts = field.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("malformed header field `{}`", field.trim()))?
+            .trim();
+        match key {
+            "x" => width = Some(parse_dimension(value)?),
+            "y" => height = Some(parse_dimension(value)?),
+            "rule" => rule = Some(Rule::parse(value)?),
+            _ => return Err(format!("unknown header field `{}`", key)),
+        }
+    }
+    let width = width.ok_or("header is missing `x`")?;
+    let height = height.ok_or("header is missing `y`")?;
+    if width as u64 * height as u64 > max_cells() {
+        return Err(format!(
+            "pattern of {}x{} exceeds the limit of {} cells",
+            width,
+            height,
+            max_cells()
+        ));
+    }
+    Ok((width, height, rule))
+}
+
+fn parse_dimension(value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(0) => Err("pattern dimensions must be positive".to_string()),
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!("invalid pattern dimension `{}`", value)),
+    }
+}
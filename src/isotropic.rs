@@ -0,0 +1,157 @@
+//! Isotropic non-totalistic rules in Hensel notation, such as `B2-a/S12`.
+//!
+//! See https://conwaylife.com/wiki/Isotropic_non-totalistic_rule for the
+//! notation. Where an outer totalistic rule only looks at how many
+//! neighbors are alive, these look at which ones, up to rotation and
+//! reflection.
+
+use crate::Cell;
+
+/// A neighborhood is a 9-bit mask of the 3x3 block in reading order, bit 0
+/// being the top-left neighbor; bit 4, the cell itself, is never set.
+const CENTER: u16 = 1 << 4;
+const NEIGHBORHOODS: usize = 512;
+
+/// Hensel letters for 1 to 4 live neighbors, in the same order as
+/// `REPRESENTATIVES`. Counts 5 to 7 reuse the letters of `8 - count`, naming
+/// the complementary neighborhood.
+const LETTERS: [&str; 4] = ["ce", "ceaikn", "ceaiknjqry", "ceaiknjqrytwz"];
+
+/// One neighborhood of each lettered configuration.
+const REPRESENTATIVES: [&[u16]; 4] = [
+    &[1, 2],
+    &[5, 10, 3, 40, 33, 68],
+    &[69, 42, 11, 7, 98, 13, 14, 70, 41, 97],
+    &[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108],
+];
+
+/// The birth and survival conditions, one flag per neighborhood.
+pub struct IsotropicRule {
+    birth: Vec<bool>,
+    survive: Vec<bool>,
+}
+
+impl IsotropicRule {
+    /// Parses `B<conditions>/S<conditions>` (either half may come first).
+    /// Each condition is a neighbor count, optionally followed by the
+    /// letters of the configurations it is limited to, or by `-` and the
+    /// letters it excludes; a bare count takes every configuration, so
+    /// totalistic rules like `B3/S23` parse as well.
+    pub fn parse(notation: &str) -> Result<IsotropicRule, String> {
+        let notation = notation.trim();
+        let invalid = || format!("invalid isotropic rule `{}`", notation);
+        let mut halves = notation.split('/');
+        let (first, second) = match (halves.next(), halves.next(), halves.next()) {
+            (Some(first), Some(second), None) => (first.trim(), second.trim()),
+            _ => return Err(invalid()),
+        };
+        let (birth, survive) = match (first.chars().next(), second.chars().next()) {
+            (Some('B'), Some('S')) | (Some('b'), Some('s')) => (&first[1..], &second[1..]),
+            (Some('S'), Some('B')) | (Some('s'), Some('b')) => (&second[1..], &first[1..]),
+            _ => return Err(invalid()),
+        };
+        Ok(IsotropicRule {
+            birth: conditions(birth)?,
+            survive: conditions(survive)?,
+        })
+    }
+
+    /// The next state of a cell whose live neighbors are `neighborhood`.
+    pub fn next(&self, cell: Cell, neighborhood: u16) -> Cell {
+        let table = match cell {
+            Cell::Alive => &self.survive,
+            Cell::Dead => &self.birth,
+        };
+        if table[(neighborhood & !CENTER) as usize] {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+/// The neighborhood bit for the neighbor at offset `(delta_r, delta_c)`.
+pub fn neighbor_bit(delta_r: i32, delta_c: i32) -> u16 {
+    1 << ((delta_r + 1) * 3 + delta_c + 1)
+}
+
+/// Flags the neighborhoods matched by one half of a rule, e.g. `2-a3`.
+fn conditions(text: &str) -> Result<Vec<bool>, String> {
+    let mut table = vec![false; NEIGHBORHOODS];
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let count = match ch.to_digit(10) {
+            Some(count) if count <= 8 => count,
+            _ => return Err(format!("unexpected `{}` in rule conditions", ch)),
+        };
+        let negated = chars.peek() == Some(&'-');
+        if negated {
+            chars.next();
+        }
+        let mut letters = String::new();
+        while let Some(&letter) = chars.peek().filter(|ch| ch.is_ascii_lowercase()) {
+            letters.push(letter);
+            chars.next();
+        }
+        if negated && letters.is_empty() {
+            return Err(format!("`{}-` is missing the letters to exclude", count));
+        }
+        let mut selected = vec![letters.is_empty() || negated; NEIGHBORHOODS];
+        for letter in letters.chars() {
+            let representative = representative(count, letter)
+                .ok_or_else(|| format!("`{}{}` is not a configuration", count, letter))?;
+            for neighborhood in orbit(representative) {
+                selected[neighborhood as usize] = !negated;
+            }
+        }
+        for neighborhood in 0..NEIGHBORHOODS as u16 {
+            if neighborhood & CENTER == 0
+                && neighborhood.count_ones() == count
+                && selected[neighborhood as usize]
+            {
+                table[neighborhood as usize] = true;
+            }
+        }
+    }
+    Ok(table)
+}
+
+/// The neighborhood `letter` names among those with `count` live neighbors.
+fn representative(count: u32, letter: char) -> Option<u16> {
+    let (group, complement) = match count {
+        1..=4 => (count as usize - 1, false),
+        5..=7 => (7 - count as usize, true),
+        _ => return None,
+    };
+    let position = LETTERS[group].find(letter)?;
+    let neighborhood = REPRESENTATIVES[group][position];
+    Some(if complement {
+        !neighborhood & (NEIGHBORHOODS as u16 - 1) & !CENTER
+    } else {
+        neighborhood
+    })
+}
+
+/// Every rotation and reflection of `neighborhood`.
+fn orbit(neighborhood: u16) -> Vec<u16> {
+    let mut orbit = Vec::with_capacity(8);
+    let mut current = neighborhood;
+    for _ in 0..4 {
+        orbit.push(current);
+        orbit.push(transform(current, |row, col| (row, 2 - col)));
+        current = transform(current, |row, col| (col, 2 - row));
+    }
+    orbit
+}
+
+/// Moves the bit for each `(row, col)` of the block to `map(row, col)`.
+fn transform<F: Fn(u16, u16) -> (u16, u16)>(neighborhood: u16, map: F) -> u16 {
+    let mut out = 0;
+    for bit in 0..9 {
+        if neighborhood & (1 << bit) != 0 {
+            let (row, col) = map(bit / 3, bit % 3);
+            out |= 1 << (row * 3 + col);
+        }
+    }
+    out
+}
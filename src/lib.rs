@@ -1,12 +1,25 @@
+mod critters;
+mod engine;
+mod font;
+mod formats;
+mod isotropic;
+mod patterns;
+mod rng;
+mod rule;
+mod save;
 mod utils;
 
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
+pub use critters::CrittersUniverse;
+pub use engine::Engine;
+pub use patterns::PatternLibrary;
+pub use utils::Timer;
+
 // use reqwest::*;
 use std::fmt;
-use wasm_bindgen::prelude::*;
 
 extern crate web_sys;
 
@@ -26,83 +39,73 @@ pub enum Cell {
 }
 
 impl Cell {
-    pub fn toggle(&mut self) {
-        *self = match *self {
+    /// The opposite state.
+    pub fn toggle(self) -> Cell {
+        match self {
             Cell::Alive => Cell::Dead,
             Cell::Dead => Cell::Alive,
         }
     }
 }
 
-pub struct Timer<'a> {
-    name: &'a str,
-}
-
-impl<'a> Timer<'a> {
-    pub fn new(name: &'a str) -> Timer<'a>{
-        web_sys::console::time_with_label(name);
-        Timer { name }
-    }
+/// A grid of cells, as exposed to JS. All of the simulation happens in the
+/// wrapped `Engine`; this adds what needs a JS host, such as the tick
+/// callback, and turns errors into `JsValue`s.
+#[wasm_bindgen]
+pub struct Universe {
+    engine: Engine,
+    on_tick: Option<js_sys::Function>,
 }
 
-impl<'a> Drop for Timer<'a> {
-    fn drop(&mut self) {
-        web_sys::console::time_end_with_label(self.name);
+impl From<Engine> for Universe {
+    fn from(engine: Engine) -> Universe {
+        Universe {
+            engine,
+            on_tick: None,
+        }
     }
 }
 
-#[wasm_bindgen]
-pub struct Universe {
-    width: u32,
-    height: u32,
-    cells: Vec<Cell>,
+fn to_js(err: String) -> JsValue {
+    JsValue::from_str(&err)
 }
 
 impl Universe {
+    /// The simulation behind this universe.
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
     pub fn get_index(&self, row: u32, column: u32) -> usize {
-        (row * self.width + column) as usize
+        self.engine.get_index(row, column)
     }
     pub fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
-        // We use self.height - 1 instead of just -1 so we wrap around the grid
-        let row_deltas = vec![self.height - 1, 0, 1];
-        let col_deltas = vec![self.width - 1, 0, 1];
-        for delta_r in row_deltas.iter() {
-            for delta_c in col_deltas.iter() {
-                if *delta_r == 0 && *delta_c == 0 {
-                    // Exclude the actual cell
-                    continue;
-                }
-                let idx = self.get_index(
-                    (row + delta_r) % self.height,
-                    (column + delta_c) % self.width,
-                );
-                count += self.cells[idx] as u8;
-            }
-        }
-        count
+        self.engine.live_neighbor_count(row, column)
+    }
+    pub fn neighbors_alive_at_least(&self, row: u32, column: u32, n: u8) -> bool {
+        self.engine.neighbors_alive_at_least(row, column, n)
+    }
+    pub fn set_kernel(&mut self, kernel: &[i32; 9]) {
+        self.engine.set_kernel(kernel)
     }
     pub fn len(&self) -> usize {
-        self.cells.len()
+        self.engine.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.engine.is_empty()
     }
     pub fn get_cells(&self) -> &[Cell] {
-        &self.cells
+        self.engine.get_cells()
     }
     pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
-        for (row, col) in cells.iter().cloned() {
-            let idx = self.get_index(row, col);
-            self.cells[idx] = Cell::Alive;
-        }
+        self.engine.set_cells(cells)
     }
 }
 
 #[wasm_bindgen]
 pub async fn run(url: String) -> Result<JsValue, JsValue> {
-    let mut opts = RequestInit::new();
-    opts.method("GET");
-    opts.mode(RequestMode::Cors);
-
-    let url = format!("{}", url);
+    let opts = RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(RequestMode::Cors);
 
     let request = Request::new_with_str_and_init(&url, &opts)?;
 
@@ -127,91 +130,509 @@ pub async fn run(url: String) -> Result<JsValue, JsValue> {
 #[wasm_bindgen]
 impl Universe {
     pub fn new() -> Self {
-        let width = 64;
-        let height = 64;
-        let cells = (0..width * height)
-            .map(|i| {
-                if i % 2 == 0 || i % 7 == 0 {
-                    Cell::Alive
-                } else {
-                    Cell::Dead
-                }
-            })
-            .collect();
-        Self {
-            width,
-            height,
-            cells,
-        }
+        utils::set_panic_hook();
+        Universe::from(Engine::new())
+    }
+    pub fn prepopulate_common_oscillators() -> Universe {
+        Universe::from(Engine::prepopulate_common_oscillators())
+    }
+    pub fn neighbor_counts(&self) -> Vec<u8> {
+        self.engine.neighbor_counts()
+    }
+    pub fn neighbor_histogram(&self) -> Vec<u32> {
+        self.engine.neighbor_histogram()
+    }
+    pub fn subgrid(
+        &self,
+        row: u32,
+        col: u32,
+        height: u32,
+        width: u32,
+    ) -> Result<Universe, JsValue> {
+        self.engine
+            .subgrid(row, col, height, width)
+            .map(Universe::from)
+            .map_err(to_js)
+    }
+    pub fn alive_neighbors_mask(&self, row: u32, column: u32) -> u8 {
+        self.engine.alive_neighbors_mask(row, column)
+    }
+    pub fn from_rle(text: &str) -> Result<Universe, JsValue> {
+        Engine::from_rle(text).map(Universe::from).map_err(to_js)
+    }
+    pub fn save(&self) -> String {
+        self.engine.save()
+    }
+    pub fn load(json: &str) -> Result<Universe, JsValue> {
+        Engine::load(json).map(Universe::from).map_err(to_js)
+    }
+    pub fn from_image_data(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        threshold: u8,
+    ) -> Result<Universe, JsValue> {
+        Engine::from_image_data(width, height, rgba, threshold)
+            .map(Universe::from)
+            .map_err(to_js)
+    }
+    pub fn from_csv(text: &str) -> Result<Universe, JsValue> {
+        Engine::from_csv(text).map(Universe::from).map_err(to_js)
+    }
+    pub fn from_lif_105(text: &str) -> Result<Universe, JsValue> {
+        Engine::from_lif_105(text)
+            .map(Universe::from)
+            .map_err(to_js)
+    }
+    pub fn from_cells_format(text: &str) -> Result<Universe, JsValue> {
+        Engine::from_cells_format(text)
+            .map(Universe::from)
+            .map_err(to_js)
+    }
+    pub fn parse(text: &str) -> Result<Universe, JsValue> {
+        Engine::parse(text).map(Universe::from).map_err(to_js)
+    }
+    pub fn randomize_region(
+        &mut self,
+        row: u32,
+        col: u32,
+        height: u32,
+        width: u32,
+        seed: u64,
+        density: f64,
+    ) {
+        self.engine
+            .randomize_region(row, col, height, width, seed, density)
+    }
+    pub fn random_walk_seed(&mut self, steps: u32, seed: u64) {
+        self.engine.random_walk_seed(steps, seed)
+    }
+    pub fn randomize(&mut self, seed: u64, density: f64) {
+        self.engine.randomize(seed, density)
+    }
+    pub fn scatter(&mut self, count: u32, seed: u64) {
+        self.engine.scatter(count, seed)
+    }
+    pub fn seed_from_bytes(&mut self, data: &[u8]) {
+        self.engine.seed_from_bytes(data)
+    }
+    pub fn soup_search(
+        width: u32,
+        height: u32,
+        generations: u32,
+        trials: u32,
+        seed: u64,
+    ) -> Result<Vec<u32>, JsValue> {
+        Engine::soup_search(width, height, generations, trials, seed).map_err(to_js)
+    }
+    pub fn simulate_to_json(
+        width: u32,
+        height: u32,
+        seed: u64,
+        density: f64,
+        steps: u32,
+    ) -> Result<String, JsValue> {
+        Engine::simulate_to_json(width, height, seed, density, steps).map_err(to_js)
+    }
+    pub fn probe_rule_stability(rule: &str, trials: u32, seed: u64) -> Result<f64, JsValue> {
+        Engine::probe_rule_stability(rule, trials, seed).map_err(to_js)
+    }
+    pub fn set_alive_probability_mask(&mut self, probs: &[f64], seed: u64) -> Result<(), JsValue> {
+        self.engine
+            .set_alive_probability_mask(probs, seed)
+            .map_err(to_js)
+    }
+    pub fn apply_noise(&mut self, rate: f64, seed: u64) {
+        self.engine.apply_noise(rate, seed)
+    }
+    pub fn clear(&mut self) {
+        self.engine.clear()
+    }
+    pub fn set_edge_cells(&mut self, alive: bool) {
+        self.engine.set_edge_cells(alive)
+    }
+    pub fn stamp_rle(&mut self, rle: &str, row: u32, col: u32) -> Result<(), JsValue> {
+        self.engine.stamp_rle(rle, row, col).map_err(to_js)
+    }
+    pub fn insert_text(&mut self, message: &str, row: u32, col: u32) -> Result<(), JsValue> {
+        self.engine.insert_text(message, row, col).map_err(to_js)
+    }
+    pub fn insert_pattern(&mut self, name: &str, row: u32, col: u32) -> Result<(), JsValue> {
+        self.engine.insert_pattern(name, row, col).map_err(to_js)
+    }
+    pub fn set_from_other(&mut self, other: &Universe) {
+        self.engine.set_from_other(&other.engine)
+    }
+    pub fn blend_universes(a: &Universe, b: &Universe, op: &str) -> Result<Universe, JsValue> {
+        Engine::blend_universes(&a.engine, &b.engine, op)
+            .map(Universe::from)
+            .map_err(to_js)
+    }
+    pub fn diff_rle(&self, other: &Universe) -> String {
+        self.engine.diff_rle(&other.engine)
+    }
+    pub fn state_hash(&self) -> u64 {
+        self.engine.state_hash()
+    }
+    pub fn step_and_detect_cycle(&mut self, max_steps: u32) -> Option<u32> {
+        self.engine.step_and_detect_cycle(max_steps)
+    }
+    /// `[onset, period]` as for `Engine::ticks_to_period`, or empty if no
+    /// cycle was found.
+    pub fn ticks_to_period(&mut self, max_steps: u32) -> Vec<u32> {
+        self.engine
+            .ticks_to_period(max_steps)
+            .map_or_else(Vec::new, |(onset, period)| vec![onset, period])
+    }
+    pub fn step_back(&mut self) -> bool {
+        self.engine.step_back()
+    }
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.engine.set_history_limit(limit)
+    }
+    pub fn tick_generations_into_history(&mut self, n: u32) {
+        self.engine.tick_generations_into_history(n)
+    }
+    pub fn snapshot_count(&self) -> usize {
+        self.engine.snapshot_count()
+    }
+    pub fn snapshot(&self, index: usize) -> Option<Universe> {
+        self.engine.snapshot(index).map(Universe::from)
+    }
+    pub fn merge_history_into_rle_animation(&self) -> String {
+        self.engine.merge_history_into_rle_animation()
+    }
+    pub fn memory_bytes(&self) -> usize {
+        self.engine.memory_bytes()
+    }
+    pub fn shift(&mut self, delta_r: i32, delta_c: i32) {
+        self.engine.shift(delta_r, delta_c)
+    }
+    pub fn auto_resize_on_edge_contact(&mut self, margin: u32) {
+        self.engine.auto_resize_on_edge_contact(margin)
+    }
+    pub fn rotate_cw(&mut self) {
+        self.engine.rotate_cw()
+    }
+    pub fn rotate_ccw(&mut self) {
+        self.engine.rotate_ccw()
+    }
+    pub fn transpose(&mut self) {
+        self.engine.transpose()
+    }
+    pub fn set_recenter(&mut self, enabled: bool) {
+        self.engine.set_recenter(enabled)
+    }
+    pub fn fit_window(&self, target_w: u32, target_h: u32) -> Vec<u32> {
+        self.engine.fit_window(target_w, target_h)
+    }
+    pub fn make_symmetric(&mut self, axis: &str) -> Result<(), JsValue> {
+        self.engine.make_symmetric(axis).map_err(to_js)
+    }
+    pub fn randomize_symmetric(
+        &mut self,
+        seed: u64,
+        density: f64,
+        axis: &str,
+    ) -> Result<(), JsValue> {
+        self.engine
+            .randomize_symmetric(seed, density, axis)
+            .map_err(to_js)
+    }
+    pub fn compare_rules(&self, rule_a: &str, rule_b: &str, steps: u32) -> Result<u32, JsValue> {
+        self.engine
+            .compare_rules(rule_a, rule_b, steps)
+            .map_err(to_js)
+    }
+    pub fn set_rule_from_numbers(&mut self, birth: &[u8], survive: &[u8]) -> Result<(), JsValue> {
+        self.engine
+            .set_rule_from_numbers(birth, survive)
+            .map_err(to_js)
+    }
+    pub fn rule_notation(&self) -> String {
+        self.engine.rule_notation()
+    }
+    pub fn contains_pattern(&self, name: &str) -> Result<bool, JsValue> {
+        self.engine.contains_pattern(name).map_err(to_js)
+    }
+    pub fn detect_spaceships(&self, max_steps: u32) -> u32 {
+        self.engine.detect_spaceships(max_steps)
+    }
+    pub fn classify_still_lifes(&self) -> Vec<String> {
+        self.engine.classify_still_lifes()
     }
     pub fn toggle_cell(&mut self, row: u32, cell: u32) {
-        let idx = self.get_index(row, cell);
-        self.cells[idx].toggle();
+        self.engine.toggle_cell(row, cell)
+    }
+    pub fn set_cell(&mut self, row: u32, column: u32, alive: bool) {
+        self.engine.set_cell(row, column, alive)
+    }
+    pub fn set_cell_checked(&mut self, row: u32, column: u32, alive: bool) -> Result<(), JsValue> {
+        self.engine
+            .set_cell_checked(row, column, alive)
+            .map_err(to_js)
+    }
+    pub fn invert(&mut self) {
+        self.engine.invert()
+    }
+    pub fn population(&self) -> u32 {
+        self.engine.population()
+    }
+    pub fn population_at(&self, generation: u32) -> Option<u32> {
+        self.engine.population_at(generation)
+    }
+    pub fn population_derivative(&self) -> i32 {
+        self.engine.population_derivative()
     }
     pub fn tick(&mut self) {
-        let _timer = Timer::new("Universe::tick");
-        // self.call_api();
-        let mut next = {
-            let _timer = Timer::new("allocate next cells");
-            self.cells.clone()
-        };
-        {
-            let _timer = Timer::new("new generation");
-            for row in 0..self.height {
-                for col in 0..self.width {
-                    let idx = self.get_index(row, col);
-                    let cell = self.cells[idx];
-                    let neighbor_count = self.live_neighbor_count(row, col);
-                    let next_cell = match (cell, neighbor_count) {
-                        // Underpopulation
-                        (Cell::Alive, x) if x < 2 => Cell::Dead,
-                        // Overpopulation
-                        (Cell::Alive, x) if x > 3 => Cell::Dead,
-                        // Reproduction
-                        (Cell::Dead, 3) => Cell::Alive,
-                        // Lives on
-                        (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                        // All other cells remain as-is
-                        (otherwise, _) => otherwise,
-                    };
-                    next[idx] = next_cell;
+        self.engine.tick()
+    }
+    pub fn tick_preserving_ptr(&mut self) -> *const Cell {
+        self.engine.tick_preserving_ptr()
+    }
+    pub fn tick_changed(&mut self) -> bool {
+        self.engine.tick_changed()
+    }
+    pub fn region_is_quiescent(&self, row: u32, col: u32, height: u32, width: u32) -> bool {
+        self.engine.region_is_quiescent(row, col, height, width)
+    }
+    /// Runs `Engine::tick_traced`, calling `cb(row, column, old, neighbors,
+    /// new)` for every cell with the states as `Cell` values. A callback
+    /// that throws is logged once and the tick carries on.
+    pub fn tick_traced(&mut self, cb: &js_sys::Function) {
+        let mut failed = false;
+        self.engine.tick_traced(|row, col, cell, count, next| {
+            let args = js_sys::Array::of5(
+                &row.into(),
+                &col.into(),
+                &(cell as u8).into(),
+                &count.into(),
+                &(next as u8).into(),
+            );
+            if let Err(err) = cb.apply(&JsValue::NULL, &args) {
+                if !failed {
+                    console_log!("tick_traced callback failed: {:?}", err);
+                    failed = true;
+                }
+            }
+        });
+    }
+    pub fn tick_weighted(&mut self) {
+        self.engine.tick_weighted()
+    }
+    pub fn tick_isotropic_rule(&mut self, notation: &str) -> Result<(), JsValue> {
+        self.engine.tick_isotropic_rule(notation).map_err(to_js)
+    }
+    pub fn apply_kernel_convolution_debug(&self) -> Vec<i32> {
+        self.engine.apply_kernel_convolution_debug()
+    }
+    /// Advances `n` generations, invoking the `set_on_tick` callback (if
+    /// any) with the new generation number after each one. A callback that
+    /// throws is logged and the run carries on.
+    pub fn tick_n(&mut self, n: u32) {
+        for _ in 0..n {
+            self.engine.tick();
+            if let Some(cb) = &self.on_tick {
+                let generation = JsValue::from(self.engine.generation());
+                if let Err(err) = cb.call1(&JsValue::NULL, &generation) {
+                    console_log!("on_tick callback failed: {:?}", err);
                 }
             }
         }
-        let _timer = Timer::new("free old cells");
-        self.cells = next;
+    }
+    pub fn run_until_population(&mut self, target: u32, max_steps: u32) -> Option<u32> {
+        self.engine.run_until_population(target, max_steps)
+    }
+    pub fn trace_glider_path(&mut self, max_steps: u32) -> Vec<f64> {
+        self.engine.trace_glider_path(max_steps)
+    }
+    /// Like `Engine::tick_frame`, but runs the generations through `tick_n`
+    /// so the `set_on_tick` callback sees each one.
+    pub fn tick_frame(&mut self, elapsed_ms: f64) -> u32 {
+        let due = self.engine.due_ticks(elapsed_ms);
+        self.tick_n(due);
+        due
+    }
+    pub fn ticks_per_second(&self) -> f64 {
+        self.engine.ticks_per_second()
+    }
+    pub fn set_ticks_per_second(&mut self, ticks_per_second: f64) -> Result<(), JsValue> {
+        self.engine
+            .set_ticks_per_second(ticks_per_second)
+            .map_err(to_js)
+    }
+    pub fn set_boundary(&mut self, name: &str) -> Result<(), JsValue> {
+        self.engine.set_boundary(name).map_err(to_js)
+    }
+    pub fn boundary_name(&self) -> String {
+        self.engine.boundary_name()
+    }
+    pub fn set_torus_offset(&mut self, delta_r: i32, delta_c: i32) {
+        self.engine.set_torus_offset(delta_r, delta_c)
+    }
+    pub fn torus_offset(&self) -> Vec<i32> {
+        self.engine.torus_offset()
+    }
+    pub fn clear_configuration(&mut self) {
+        self.engine.clear_configuration()
+    }
+    pub fn escaped_count(&self) -> u32 {
+        self.engine.escaped_count()
+    }
+    pub fn glider_count_escaped_per_edge(&self) -> Vec<u32> {
+        self.engine.glider_count_escaped_per_edge()
+    }
+    pub fn set_tick_timing(&mut self, limit: usize) {
+        self.engine.set_tick_timing(limit)
+    }
+    pub fn tick_times_ms(&self) -> Vec<f64> {
+        self.engine.tick_times_ms()
+    }
+    pub fn set_on_tick(&mut self, cb: js_sys::Function) {
+        self.on_tick = Some(cb);
+    }
+    pub fn clear_on_tick(&mut self) {
+        self.on_tick = None;
+    }
+    pub fn generation(&self) -> u32 {
+        self.engine.generation()
+    }
+    pub fn set_generation(&mut self, generation: u32) {
+        self.engine.set_generation(generation)
+    }
+    pub fn generation_at_extinction(&self) -> Option<u32> {
+        self.engine.generation_at_extinction()
     }
     pub fn render(&self) -> String {
-        self.to_string()
+        self.engine.render()
+    }
+    pub fn to_ascii_grid(&self, alive: char, dead: char, trailing_newline: bool) -> String {
+        self.engine.to_ascii_grid(alive, dead, trailing_newline)
+    }
+    pub fn to_lif_105(&self) -> String {
+        self.engine.to_lif_105()
+    }
+    pub fn export_csv(&self) -> String {
+        self.engine.export_csv()
+    }
+    pub fn render_braille(&self) -> String {
+        self.engine.render_braille()
+    }
+    pub fn changed_cells(&self) -> Vec<u32> {
+        self.engine.changed_cells()
+    }
+    pub fn highlight_changed_since(&self, snapshot: &Universe) -> Result<Vec<u32>, JsValue> {
+        self.engine
+            .highlight_changed_since(&snapshot.engine)
+            .map_err(to_js)
+    }
+    pub fn count_births_deaths_last_tick(&self) -> Vec<u32> {
+        self.engine.count_births_deaths_last_tick()
+    }
+    pub fn render_patches(&self, alive: u32, dead: u32) -> Vec<u32> {
+        self.engine.render_patches(alive, dead)
+    }
+    pub fn render_rgba(&self, alive: u32, dead: u32) -> Vec<u8> {
+        self.engine.render_rgba(alive, dead)
+    }
+    pub fn render_rgba_scaled(
+        &self,
+        cell_px: u32,
+        alive: u32,
+        dead: u32,
+    ) -> Result<Vec<u8>, JsValue> {
+        self.engine
+            .render_rgba_scaled(cell_px, alive, dead)
+            .map_err(to_js)
+    }
+    pub fn capture_frames(&mut self, count: u32, alive: u32, dead: u32) -> Vec<u8> {
+        self.engine.capture_frames(count, alive, dead)
+    }
+    pub fn to_svg(&self, cell_size: u32, alive: &str, dead: bool) -> String {
+        self.engine.to_svg(cell_size, alive, dead)
+    }
+    pub fn set_track_ages(&mut self, enabled: bool) {
+        self.engine.set_track_ages(enabled)
+    }
+    pub fn ages(&self) -> Vec<u32> {
+        self.engine.ages()
+    }
+    pub fn render_rgba_with_trails(&self, alive: u32, dead: u32, trail_len: u32) -> Vec<u8> {
+        self.engine.render_rgba_with_trails(alive, dead, trail_len)
     }
     pub fn width(&self) -> u32 {
-        self.width
+        self.engine.width()
     }
     pub fn height(&self) -> u32 {
-        self.height
+        self.engine.height()
     }
     pub fn cells(&self) -> *const Cell {
-        self.cells.as_ptr()
+        self.engine.cells()
+    }
+    pub fn as_bool_vec(&self) -> Vec<u8> {
+        self.engine.as_bool_vec()
+    }
+    pub fn to_packed_u32_rows(&self) -> Vec<u32> {
+        self.engine.to_packed_u32_rows()
+    }
+    pub fn as_run_length_rows(&self) -> Vec<u32> {
+        self.engine.as_run_length_rows()
+    }
+    pub fn alive_indices(&self) -> Vec<u32> {
+        self.engine.alive_indices()
+    }
+    pub fn quadrant_populations(&self) -> Vec<u32> {
+        self.engine.quadrant_populations()
+    }
+    pub fn row_col_of_index(&self, index: usize) -> Vec<u32> {
+        self.engine.row_col_of_index(index)
+    }
+    pub fn is_within_bounds(&self, row: u32, column: u32) -> bool {
+        self.engine.is_within_bounds(row, column)
+    }
+    pub fn neighbors_wrapped_flag(&self, row: u32, column: u32) -> bool {
+        self.engine.neighbors_wrapped_flag(row, column)
+    }
+    pub fn bit_at(&self, index: usize) -> bool {
+        self.engine.bit_at(index)
+    }
+    pub fn activity(&self) -> Vec<u32> {
+        self.engine.activity()
+    }
+    pub fn reset_activity(&mut self) {
+        self.engine.reset_activity()
+    }
+    pub fn write_cells(&mut self, data: &[u8]) -> Result<(), JsValue> {
+        self.engine.write_cells(data).map_err(to_js)
+    }
+    pub fn cells_version(&self) -> u32 {
+        self.engine.cells_version()
+    }
+    pub fn grow(&mut self, margin: u32) {
+        self.engine.grow(margin)
+    }
+    pub fn enforce_max_dimensions(limit: u32) -> Result<(), JsValue> {
+        Engine::enforce_max_dimensions(u64::from(limit)).map_err(to_js)
+    }
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), JsValue> {
+        self.engine.resize(width, height).map_err(to_js)
     }
     pub fn set_width(&mut self, width: u32) {
-        self.width = width;
-        self.cells = (0..width * self.height).map(|_| Cell::Dead).collect();
+        self.engine.set_width(width)
     }
     pub fn set_height(&mut self, height: u32) {
-        self.height = height;
-        self.cells = (0..self.width * height).map(|_| Cell::Dead).collect();
+        self.engine.set_height(height)
+    }
+}
+
+impl Default for Universe {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl fmt::Display for Universe {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for line in self.cells.as_slice().chunks(self.width as usize) {
-            for &cell in line {
-                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
-                write!(f, "{}", symbol)?;
-            }
-            write!(f, "\n")?;
-        }
-        Ok(())
+        self.engine.fmt(f)
     }
 }
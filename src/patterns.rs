@@ -0,0 +1,98 @@
+//! Built-in catalog of well-known patterns, stored as RLE.
+
+use wasm_bindgen::prelude::*;
+
+pub const CATALOG: &[(&str, &str)] = &[
+    ("block", "x = 2, y = 2\n2o$2o!"),
+    ("beehive", "x = 4, y = 3\nb2o$o2bo$b2o!"),
+    ("loaf", "x = 4, y = 4\nb2o$o2bo$bobo$2bo!"),
+    ("boat", "x = 3, y = 3\n2o$obo$bo!"),
+    ("tub", "x = 3, y = 3\nbo$obo$bo!"),
+    ("blinker", "x = 3, y = 1\n3o!"),
+    ("glider", "x = 3, y = 3\nbob$2bo$3o!"),
+    ("toad", "x = 4, y = 2\nb3o$3o!"),
+    ("beacon", "x = 4, y = 4\n2o$o$3bo$2b2o!"),
+    ("lwss", "x = 5, y = 4\nbo2bo$o$o3bo$4o!"),
+    (
+        "pulsar",
+        "x = 13, y = 13\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+         o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    ),
+    ("pentadecathlon", "x = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!"),
+    ("r_pentomino", "x = 3, y = 3\nb2o$2o$bo!"),
+    ("diehard", "x = 8, y = 3\n6bo$2o$bo3b3o!"),
+    (
+        "gosper_glider_gun",
+        "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+         2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+];
+
+/// Catalog entries that `classify` recognizes: common still lifes and the
+/// blinker, the objects a random soup most often settles into.
+const CENSUS: &[&str] = &["block", "beehive", "loaf", "boat", "tub", "blinker"];
+
+/// The built-in pattern catalog, for populating a "load pattern" menu.
+#[wasm_bindgen]
+pub struct PatternLibrary;
+
+#[wasm_bindgen]
+impl PatternLibrary {
+    pub fn names() -> Vec<String> {
+        CATALOG.iter().map(|(name, _)| name.to_string()).collect()
+    }
+    pub fn get_rle(name: &str) -> Option<String> {
+        rle(name).map(str::to_string)
+    }
+}
+
+pub fn rle(name: &str) -> Option<&'static str> {
+    CATALOG
+        .iter()
+        .find(|(entry, _)| *entry == name)
+        .map(|(_, rle)| *rle)
+}
+
+/// The name of the `CENSUS` object whose live cells are `cells` (in any
+/// position and orientation), if any.
+pub fn classify(cells: &[(i64, i64)]) -> Option<&'static str> {
+    let cells = normalize(cells);
+    CENSUS.iter().cloned().find(|&name| {
+        let rle = rle(name).expect("census entries are in the catalog");
+        let pattern = crate::formats::rle::parse(rle).expect("catalog patterns parse");
+        orientations(&pattern.cells).contains(&cells)
+    })
+}
+
+/// Live cells of a pattern in each of its distinct orientations (the four
+/// rotations of the pattern and of its mirror image), each normalized so
+/// its bounding box starts at `(0, 0)`.
+pub fn orientations(cells: &[(u32, u32)]) -> Vec<Vec<(u32, u32)>> {
+    let mut result: Vec<Vec<(u32, u32)>> = Vec::new();
+    let mut current: Vec<(i64, i64)> = cells.iter().map(|&(r, c)| (r as i64, c as i64)).collect();
+    for mirrored in 0..2 {
+        if mirrored == 1 {
+            current = current.iter().map(|&(r, c)| (r, -c)).collect();
+        }
+        for _ in 0..4 {
+            current = current.iter().map(|&(r, c)| (c, -r)).collect();
+            let normalized = normalize(&current);
+            if !result.contains(&normalized) {
+                result.push(normalized);
+            }
+        }
+    }
+    result
+}
+
+/// `cells` shifted so their bounding box starts at `(0, 0)`, sorted.
+pub fn normalize(cells: &[(i64, i64)]) -> Vec<(u32, u32)> {
+    let min_r = cells.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_c = cells.iter().map(|p| p.1).min().unwrap_or(0);
+    let mut normalized: Vec<(u32, u32)> = cells
+        .iter()
+        .map(|&(r, c)| ((r - min_r) as u32, (c - min_c) as u32))
+        .collect();
+    normalized.sort_unstable();
+    normalized
+}
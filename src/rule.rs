@@ -0,0 +1,103 @@
+//! Outer totalistic birth/survival rules such as Conway's B3/S23.
+
+use crate::Cell;
+
+/// Largest neighbor count a rule can refer to in the Moore neighborhood.
+pub const MAX_NEIGHBORS: u8 = 8;
+
+/// Bit `n` of `birth` (`survive`) is set when a dead (live) cell with `n`
+/// live neighbors is alive in the next generation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: u32,
+    survive: u32,
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23.
+    pub fn conway() -> Rule {
+        Rule {
+            birth: 1 << 3,
+            survive: 1 << 2 | 1 << 3,
+        }
+    }
+
+    pub fn from_counts(birth: &[u8], survive: &[u8]) -> Result<Rule, String> {
+        Ok(Rule {
+            birth: mask(birth)?,
+            survive: mask(survive)?,
+        })
+    }
+
+    /// Parses `B3/S23` notation (either half may come first, letters in
+    /// either case) or the older survival/birth form `23/3`.
+    pub fn parse(notation: &str) -> Result<Rule, String> {
+        let notation = notation.trim();
+        let invalid = || format!("invalid rule `{}`", notation);
+        let mut halves = notation.split('/');
+        let (first, second) = match (halves.next(), halves.next(), halves.next()) {
+            (Some(first), Some(second), None) => (first.trim(), second.trim()),
+            _ => return Err(invalid()),
+        };
+        let (birth, survive) = match (first.chars().next(), second.chars().next()) {
+            (Some('B'), Some('S')) | (Some('b'), Some('s')) => (&first[1..], &second[1..]),
+            (Some('S'), Some('B')) | (Some('s'), Some('b')) => (&second[1..], &first[1..]),
+            _ => (second, first),
+        };
+        Rule::from_counts(
+            &digits(birth).ok_or_else(invalid)?,
+            &digits(survive).ok_or_else(invalid)?,
+        )
+    }
+
+    /// The rule in `B3/S23` notation, as accepted by `parse`.
+    pub fn notation(&self) -> String {
+        format!("B{}/S{}", counts(self.birth), counts(self.survive))
+    }
+
+    /// The rule in the older survival/birth form `23/3`, as accepted by
+    /// `parse`.
+    pub fn survival_birth_notation(&self) -> String {
+        format!("{}/{}", counts(self.survive), counts(self.birth))
+    }
+
+    pub fn next(&self, cell: Cell, neighbors: u8) -> Cell {
+        let mask = match cell {
+            Cell::Alive => self.survive,
+            Cell::Dead => self.birth,
+        };
+        if neighbors <= MAX_NEIGHBORS && mask & (1 << neighbors) != 0 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+/// The neighbor counts set in `mask`, as ascending digits.
+fn counts(mask: u32) -> String {
+    (0..=MAX_NEIGHBORS)
+        .filter(|&n| mask & (1 << n) != 0)
+        .map(|n| char::from(b'0' + n))
+        .collect()
+}
+
+fn digits(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .map(|c| c.to_digit(10).map(|digit| digit as u8))
+        .collect()
+}
+
+fn mask(counts: &[u8]) -> Result<u32, String> {
+    let mut mask = 0;
+    for &count in counts {
+        if count > MAX_NEIGHBORS {
+            return Err(format!(
+                "neighbor count {} is larger than the maximum of {}",
+                count, MAX_NEIGHBORS
+            ));
+        }
+        mask |= 1 << count;
+    }
+    Ok(mask)
+}
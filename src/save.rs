@@ -0,0 +1,77 @@
+//! A JSON save file holding everything needed to resume a simulation.
+//!
+//! Cells are packed eight to a byte, row-major, with the first cell of each
+//! byte in its lowest bit. The rule is stored in `B3/S23` notation so save
+//! files stay readable and editable by hand.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Boundary, Engine};
+use crate::formats::max_cells;
+use crate::rule::Rule;
+use crate::Cell;
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveFile {
+    pub width: u32,
+    pub height: u32,
+    pub generation: u32,
+    pub rule: String,
+    pub boundary: String,
+    pub cells: Vec<u8>,
+    /// Absent from files written before twisted tori, which load untwisted.
+    #[serde(default)]
+    pub torus_offset: (i32, i32),
+}
+
+impl SaveFile {
+    pub fn from_engine(engine: &Engine) -> SaveFile {
+        let mut cells = vec![0u8; engine.cells.len().div_ceil(8)];
+        for (idx, &cell) in engine.cells.iter().enumerate() {
+            cells[idx / 8] |= (cell as u8) << (idx % 8);
+        }
+        SaveFile {
+            width: engine.width,
+            height: engine.height,
+            generation: engine.generation,
+            rule: engine.rule.notation(),
+            boundary: engine.boundary.name().to_string(),
+            cells,
+            torus_offset: engine.torus_offset,
+        }
+    }
+
+    pub fn into_engine(self) -> Result<Engine, String> {
+        let len = self.width as u64 * self.height as u64;
+        if len > max_cells() {
+            return Err(format!(
+                "{}x{} grid is larger than the maximum of {} cells",
+                self.width,
+                self.height,
+                max_cells()
+            ));
+        }
+        let len = len as usize;
+        if self.cells.len() != len.div_ceil(8) {
+            return Err(format!(
+                "expected {} bytes of cells for a {}x{} grid, got {}",
+                len.div_ceil(8),
+                self.width,
+                self.height,
+                self.cells.len()
+            ));
+        }
+        let mut engine = Engine::empty(self.width, self.height);
+        engine.rule = Rule::parse(&self.rule)?;
+        engine.boundary = Boundary::parse(&self.boundary)?;
+        engine.torus_offset = self.torus_offset;
+        for idx in 0..len {
+            if self.cells[idx / 8] >> (idx % 8) & 1 == 1 {
+                engine.cells[idx] = Cell::Alive;
+            }
+        }
+        engine.recount();
+        engine.set_generation(self.generation);
+        Ok(engine)
+    }
+}
@@ -5,6 +5,61 @@ pub fn set_panic_hook() {
     //
     // For more details see
     // https://github.com/rustwasm/console_error_panic_hook#readme
-    #[cfg(feature = "console_error_panic_hook")]
+    #[cfg(all(feature = "console_error_panic_hook", target_arch = "wasm32"))]
     console_error_panic_hook::set_once();
 }
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+/// Measures elapsed wall time: `performance.now()` in wasm and `Instant`
+/// natively, where there is no JS to call.
+pub struct Stopwatch {
+    #[cfg(target_arch = "wasm32")]
+    start: f64,
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+}
+
+impl Stopwatch {
+    pub fn start() -> Stopwatch {
+        Stopwatch {
+            #[cfg(target_arch = "wasm32")]
+            start: performance_now(),
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    pub fn elapsed_ms(&self) -> f64 {
+        #[cfg(target_arch = "wasm32")]
+        return performance_now() - self.start;
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.start.elapsed().as_secs_f64() * 1000.0;
+    }
+}
+
+pub struct Timer<'a> {
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    name: &'a str,
+}
+
+// Console timers only exist in JS; native builds (e.g. tests) skip them.
+impl<'a> Timer<'a> {
+    pub fn new(name: &'a str) -> Timer<'a> {
+        #[cfg(target_arch = "wasm32")]
+        web_sys::console::time_with_label(name);
+        Timer { name }
+    }
+}
+
+impl<'a> Drop for Timer<'a> {
+    fn drop(&mut self) {
+        #[cfg(target_arch = "wasm32")]
+        web_sys::console::time_end_with_label(self.name);
+    }
+}
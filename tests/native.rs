@@ -0,0 +1,404 @@
+//! Tests that run natively with `cargo test`, for the parts of the
+//! universe that don't need a JS host.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_game_of_life::{Cell, Engine, Universe};
+
+fn sized_engine(width: u32, height: u32) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_width(width);
+    engine.set_height(height);
+    engine
+}
+
+#[test]
+pub fn test_tick_times_are_recorded_and_capped() {
+    let mut universe = Universe::new();
+    universe.tick();
+    assert!(universe.tick_times_ms().is_empty());
+
+    universe.set_tick_timing(5);
+    for recorded in 1..=3 {
+        universe.tick();
+        assert_eq!(universe.tick_times_ms().len(), recorded);
+    }
+    for _ in 0..10 {
+        universe.tick();
+    }
+    let times = universe.tick_times_ms();
+    assert_eq!(times.len(), 5);
+    assert!(times.iter().all(|&ms| ms.is_finite() && ms >= 0.0));
+
+    universe.set_tick_timing(2);
+    assert_eq!(universe.tick_times_ms(), times[3..].to_vec());
+    universe.set_tick_timing(0);
+    universe.tick();
+    assert!(universe.tick_times_ms().is_empty());
+}
+
+#[test]
+pub fn test_engine_moves_glider() {
+    let mut engine = sized_engine(6, 6);
+    engine.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+    engine.tick();
+    let mut expected = sized_engine(6, 6);
+    expected.set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)]);
+    assert_eq!(engine.get_cells(), expected.get_cells());
+    assert_eq!(engine.generation(), 1);
+    assert_eq!(engine.population(), 5);
+}
+
+#[test]
+pub fn test_engine_errors_are_strings() {
+    assert!(Engine::from_rle("x = 3, y = 3\n4o!").is_err());
+    let mut engine = sized_engine(4, 4);
+    assert_eq!(
+        engine.set_boundary("klein"),
+        Err("unknown boundary `klein`".to_string())
+    );
+    assert_eq!(
+        engine.subgrid(5, 0, 1, 1).err(),
+        Some("subgrid origin is outside the universe".to_string())
+    );
+    assert!(engine.set_ticks_per_second(0.0).is_err());
+    assert!(engine.insert_pattern("no_such_pattern", 0, 0).is_err());
+}
+
+#[test]
+pub fn test_engine_tick_frame() {
+    let mut engine = sized_engine(8, 8);
+    engine.set_ticks_per_second(10.0).unwrap();
+    assert_eq!(engine.tick_frame(50.0), 0);
+    assert_eq!(engine.tick_frame(60.0), 1);
+    assert_eq!(engine.tick_frame(290.0), 3);
+    assert_eq!(engine.generation(), 4);
+}
+
+#[test]
+pub fn test_narrow_torus_counts_wrapped_neighbors_per_position() {
+    // From 3x3 up, every neighbor position is a different cell.
+    let mut engine = sized_engine(3, 3);
+    engine.set_cells(&[(1, 1)]);
+    assert_eq!(engine.neighbor_counts(), vec![1, 1, 1, 1, 0, 1, 1, 1, 1]);
+
+    let mut engine = sized_engine(2, 2);
+    engine.set_cells(&[(0, 0)]);
+    assert_eq!(engine.neighbor_counts(), vec![0, 2, 2, 4]);
+    assert_eq!(engine.live_neighbor_count(1, 1), 4);
+    engine.set_boundary("dead").unwrap();
+    assert_eq!(engine.live_neighbor_count(1, 1), 1);
+
+    let mut engine = sized_engine(1, 1);
+    engine.set_cells(&[(0, 0)]);
+    assert_eq!(engine.live_neighbor_count(0, 0), 8);
+}
+
+#[test]
+pub fn test_engine_save_load_round_trip() {
+    let mut engine = sized_engine(10, 8);
+    engine.insert_pattern("glider", 1, 1).unwrap();
+    engine.set_boundary("dead").unwrap();
+    engine.tick_n(3);
+    let mut loaded = Engine::load(&engine.save()).unwrap();
+    assert_eq!(loaded.generation(), 3);
+    engine.tick_n(5);
+    loaded.tick_n(5);
+    assert_eq!(loaded.get_cells(), engine.get_cells());
+    assert!(Engine::load("{}").is_err());
+    let klein = engine.save().replace("\"dead\"", "\"klein\"");
+    assert_eq!(
+        Engine::load(&klein).err(),
+        Some("unknown boundary `klein`".to_string())
+    );
+}
+
+#[test]
+pub fn test_engine_save_load_keeps_torus_offset() {
+    let mut engine = sized_engine(8, 8);
+    engine.insert_pattern("glider", 4, 4).unwrap();
+    engine.set_torus_offset(1, 3);
+    let saved = engine.save();
+    let mut loaded = Engine::load(&saved).unwrap();
+    assert_eq!(loaded.torus_offset(), vec![1, 3]);
+    engine.tick_n(12);
+    loaded.tick_n(12);
+    assert_eq!(loaded.get_cells(), engine.get_cells());
+
+    // Files from before twisted tori have no offset and load untwisted.
+    let old = Engine::load(&saved.replace(",\"torus_offset\":[1,3]", "")).unwrap();
+    assert_eq!(old.torus_offset(), vec![0, 0]);
+}
+
+#[test]
+pub fn test_universe_delegates_to_engine() {
+    let mut universe = Universe::new();
+    universe.set_width(5);
+    universe.set_height(5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.tick();
+    assert_eq!(universe.get_cells(), universe.engine().get_cells());
+    assert_eq!(universe.get_cells()[universe.get_index(1, 2)], Cell::Alive);
+    assert_eq!(universe.render(), universe.engine().render());
+}
+
+#[test]
+pub fn test_engine_tick_traced_matches_tick() {
+    let mut traced = sized_engine(7, 5);
+    traced.set_cells(&[(2, 2), (2, 3), (2, 4)]);
+    let mut plain = sized_engine(7, 5);
+    plain.set_cells(&[(2, 2), (2, 3), (2, 4)]);
+    let mut seen = Vec::new();
+    traced.tick_traced(|row, col, old, count, new| seen.push((row, col, old, count, new)));
+    plain.tick();
+    assert_eq!(seen.len(), 35);
+    assert_eq!(seen[2 * 7 + 2], (2, 2, Cell::Alive, 1, Cell::Dead));
+    assert_eq!(traced.get_cells(), plain.get_cells());
+}
+
+#[test]
+pub fn test_soup_search_is_deterministic() {
+    let peaks = Engine::soup_search(16, 16, 40, 6, 2024).unwrap();
+    assert_eq!(peaks.len(), 6);
+    assert_eq!(peaks, Engine::soup_search(16, 16, 40, 6, 2024).unwrap());
+    assert_ne!(peaks, Engine::soup_search(16, 16, 40, 6, 2025).unwrap());
+    // Half-density soups never start empty.
+    assert!(peaks.iter().all(|&peak| peak > 0));
+}
+
+#[test]
+pub fn test_cell_toggle() {
+    assert_eq!(Cell::Alive.toggle(), Cell::Dead);
+    assert_eq!(Cell::Dead.toggle(), Cell::Alive);
+}
+
+#[test]
+pub fn test_probe_rule_stability() {
+    let life = Engine::probe_rule_stability("B3/S23", 8, 7).unwrap();
+    let death = Engine::probe_rule_stability("B/S", 8, 7).unwrap();
+    assert_eq!(death, 1.0);
+    assert!(life < death, "Life scored {}", life);
+    assert_eq!(life, Engine::probe_rule_stability("B3/S23", 8, 7).unwrap());
+    assert!(Engine::probe_rule_stability("B9/S", 8, 7).is_err());
+}
+
+#[test]
+pub fn test_set_generation() {
+    let mut engine = sized_engine(12, 12);
+    engine.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    let cells = engine.as_bool_vec();
+    engine.set_generation(500);
+    assert_eq!(engine.generation(), 500);
+    assert_eq!(engine.as_bool_vec(), cells);
+    assert_eq!(engine.population(), 5);
+
+    engine.tick();
+    let loaded = Engine::load(&engine.save()).unwrap();
+    assert_eq!(loaded.generation(), 501);
+    assert_eq!(loaded.as_bool_vec(), engine.as_bool_vec());
+}
+
+#[test]
+pub fn test_isotropic_letters() {
+    // Births at the middle of a 5x5 grid under a dead boundary, given the
+    // live cells around it.
+    let born = |notation: &str, neighbors: &[(u32, u32)]| {
+        let mut engine = sized_engine(5, 5);
+        engine.set_boundary("dead").unwrap();
+        engine.set_cells(neighbors);
+        engine.tick_isotropic_rule(notation).unwrap();
+        engine.bit_at(engine.get_index(2, 2))
+    };
+    let vertical = [(1, 2), (3, 2)];
+    let corner = [(1, 2), (2, 3)];
+    assert!(born("B2i/S", &vertical));
+    assert!(!born("B2e/S", &vertical));
+    assert!(born("B2e/S", &corner));
+    assert!(!born("B2-e/S", &corner));
+    assert!(born("B3y/S", &[(1, 1), (1, 3), (3, 2)]));
+    assert!(born(
+        "B7c/S",
+        &[(1, 1), (1, 2), (1, 3), (2, 1), (2, 3), (3, 1), (3, 2)]
+    ));
+
+    // The letters of each count split its neighborhoods between them.
+    let births = |notation: String| {
+        let mut engine = sized_engine(64, 64);
+        engine.randomize(7, 0.5);
+        engine.tick_isotropic_rule(&notation).unwrap();
+        engine.population()
+    };
+    for (count, letters) in [
+        (1, "ce"),
+        (2, "ceaikn"),
+        (3, "ceaiknjqry"),
+        (4, "ceaiknjqrytwz"),
+        (6, "ceaikn"),
+    ]
+    .iter()
+    {
+        let total = births(format!("B{}/S", count));
+        assert!(total > 0);
+        let split: u32 = letters
+            .chars()
+            .map(|letter| births(format!("B{}{}/S", count, letter)))
+            .sum();
+        assert_eq!(split, total);
+    }
+
+    for notation in ["B3/S23/", "B9/S", "B2x/S", "B0c/S", "B2-/S", "23/3"].iter() {
+        assert!(sized_engine(4, 4).tick_isotropic_rule(notation).is_err());
+    }
+}
+
+#[test]
+pub fn test_render_rgba_scaled_rejects_oversized_images() {
+    let engine = sized_engine(64, 64);
+    assert_eq!(
+        engine.render_rgba_scaled(65, 0, 0),
+        Err("a 64x64 grid at 65 pixels per cell exceeds the limit of 16777216 pixels".to_string())
+    );
+    assert!(engine.render_rgba_scaled(u32::MAX, 0, 0).is_err());
+    assert_eq!(
+        engine.render_rgba_scaled(2, 0, 0).unwrap().len(),
+        64 * 64 * 4 * 4
+    );
+}
+
+#[test]
+pub fn test_from_rle_reads_the_rule() {
+    let highlife = Engine::from_rle("x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!").unwrap();
+    assert_eq!(highlife.rule_notation(), "B36/S23");
+    assert_eq!(highlife.population(), 5);
+    let survival_birth = Engine::from_rle("x = 1, y = 1, rule = 23/36\no!").unwrap();
+    assert_eq!(survival_birth.rule_notation(), "B36/S23");
+    let conway = Engine::from_rle("x = 1, y = 1\no!").unwrap();
+    assert_eq!(conway.rule_notation(), "B3/S23");
+
+    assert!(Engine::from_rle("x = 3, y = 3, rule = garbage\nbo$2bo$3o!").is_err());
+    let mut engine = sized_engine(8, 8);
+    assert!(engine
+        .stamp_rle("x = 1, y = 1, rule = B9/S\no!", 0, 0)
+        .is_err());
+    engine
+        .stamp_rle("x = 1, y = 1, rule = B36/S23\no!", 0, 0)
+        .unwrap();
+    assert_eq!(engine.rule_notation(), "B3/S23");
+}
+
+#[test]
+pub fn test_lif_105_round_trips_the_rule() {
+    let mut engine = sized_engine(12, 12);
+    engine.set_cells(&[(4, 4), (4, 5), (4, 6), (5, 4), (6, 5), (8, 8)]);
+    engine.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    let mut loaded = Engine::from_lif_105(&engine.to_lif_105()).unwrap();
+    assert_eq!(loaded.rule_notation(), "B36/S23");
+    assert_eq!(
+        Engine::parse(&engine.to_lif_105()).unwrap().rule_notation(),
+        "B36/S23"
+    );
+
+    let conway = Engine::from_lif_105("#Life 1.05\n#N\n#P 0 0\n*\n").unwrap();
+    assert_eq!(conway.rule_notation(), "B3/S23");
+
+    // Compare on matching grids: the loaded one is cropped to the pattern.
+    let mut expected = sized_engine(loaded.width(), loaded.height());
+    expected.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    expected.set_cells(&[(0, 0), (0, 1), (0, 2), (1, 0), (2, 1), (4, 4)]);
+    assert_eq!(loaded.get_cells(), expected.get_cells());
+    loaded.tick_n(3);
+    expected.tick_n(3);
+    assert_eq!(loaded.get_cells(), expected.get_cells());
+}
+
+#[test]
+pub fn test_lif_105_rejects_malformed_input() {
+    for text in [
+        "",
+        "#Life 1.06\n0 0\n",
+        "#Life 1.05\n*\n",
+        "#Life 1.05\n#P 0\n*\n",
+        "#Life 1.05\n#P 0 0\n*o\n",
+        "#Life 1.05\n#P 0 0\n..\n",
+        "#Life 1.05\n#P 0 0\n*\n#P 99999 99999\n*\n",
+        "#Life 1.05\n#R 2x/3\n#P 0 0\n*\n",
+    ]
+    .iter()
+    {
+        assert!(Engine::from_lif_105(text).is_err(), "{:?}", text);
+    }
+}
+
+#[test]
+pub fn test_tick_preserving_ptr() {
+    let mut engine = sized_engine(8, 8);
+    engine.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    let ptr = engine.cells();
+    let version = engine.cells_version();
+    for _ in 0..6 {
+        engine.tick();
+        assert_eq!(engine.cells(), ptr);
+    }
+
+    // Even with auto-resizing on and the glider at the edge.
+    engine.auto_resize_on_edge_contact(2);
+    for _ in 0..6 {
+        assert_eq!(engine.tick_preserving_ptr(), ptr);
+        assert_eq!(engine.cells(), ptr);
+    }
+    assert_eq!(engine.cells_version(), version);
+    assert_eq!(engine.width(), 8);
+    assert_eq!(engine.generation(), 12);
+
+    engine.tick();
+    assert_eq!(engine.width(), 12);
+    assert_ne!(engine.cells_version(), version);
+}
+
+#[test]
+pub fn test_enforce_max_dimensions() {
+    let big = sized_engine(30, 30).save();
+    let mut engine = sized_engine(20, 20);
+    Engine::enforce_max_dimensions(400).unwrap();
+    assert!(Engine::from_rle("x = 100000, y = 100000\n!").is_err());
+    assert!(Engine::from_rle("x = 21, y = 20\n!").is_err());
+    assert!(Engine::from_rle("x = 20, y = 20\n!").is_ok());
+    assert!(Engine::load(&big).is_err());
+    assert!(Engine::from_image_data(30, 30, &[0; 30 * 30 * 4], 0).is_err());
+    assert!(Engine::soup_search(30, 30, 1, 1, 0).is_err());
+    assert!(Engine::soup_search(u32::MAX, u32::MAX, 1, 1, 0).is_err());
+
+    assert_eq!(
+        engine.resize(40, 20),
+        Err("40x20 grid exceeds the limit of 400 cells".to_string())
+    );
+    engine.set_height(21);
+    engine.grow(1);
+    assert_eq!((engine.width(), engine.height()), (20, 20));
+    Engine::enforce_max_dimensions(1 << 24).unwrap();
+    assert!(Engine::load(&big).is_ok());
+    assert!(Engine::enforce_max_dimensions(1 << 32).is_err());
+}
+
+#[test]
+pub fn test_lifespan_histogram() {
+    let engine = sized_engine(16, 16);
+    let histogram = engine.lifespan_histogram(20, 300, 5);
+    assert_eq!(histogram.len(), 301);
+    assert_eq!(histogram.iter().sum::<u32>(), 20);
+    assert_eq!(engine.lifespan_histogram(20, 300, 5), histogram);
+    assert_eq!(engine.generation(), 0);
+
+    let short = engine.lifespan_histogram(7, 0, 5);
+    assert_eq!(short, vec![7]);
+}
+
+#[test]
+pub fn test_insert_text_rejects_unknown_characters() {
+    let mut engine = sized_engine(16, 8);
+    assert_eq!(
+        engine.insert_text("Hi~", 0, 0),
+        Err("no glyph for `~`".to_string())
+    );
+    assert_eq!(engine.population(), 0);
+}
@@ -3,11 +3,16 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_game_of_life;
-use wasm_game_of_life::Universe;
+use wasm_game_of_life::{Cell, CrittersUniverse, PatternLibrary, Universe};
 
 extern crate wasm_bindgen_test;
 use wasm_bindgen_test::*;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
 wasm_bindgen_test_configure!(run_in_browser);
 
 #[cfg(test)]
@@ -35,3 +40,1952 @@ pub fn test_tick() {
     input_universe.tick();
     assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
 }
+
+#[cfg(test)]
+pub fn random_universe(width: u32, height: u32, seed: u64) -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(width);
+    universe.set_height(height);
+    let mut state = seed;
+    let mut cells = Vec::new();
+    for row in 0..height {
+        for col in 0..width {
+            // Plain LCG; good enough to scatter cells across the grid.
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            if (state >> 60) < 5 {
+                cells.push((row, col));
+            }
+        }
+    }
+    universe.set_cells(&cells);
+    universe
+}
+
+#[cfg(test)]
+pub fn reference_tick(universe: &Universe) -> Vec<Cell> {
+    let mut next = Vec::new();
+    for row in 0..universe.height() {
+        for col in 0..universe.width() {
+            let cell = universe.get_cells()[universe.get_index(row, col)];
+            let next_cell = match (cell, universe.live_neighbor_count(row, col)) {
+                (Cell::Alive, 2) | (Cell::Alive, 3) | (Cell::Dead, 3) => Cell::Alive,
+                _ => Cell::Dead,
+            };
+            next.push(next_cell);
+        }
+    }
+    next
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_counts_match_live_neighbor_count() {
+    for seed in 0..4 {
+        let universe = random_universe(13, 9, seed);
+        let counts = universe.neighbor_counts();
+        for row in 0..universe.height() {
+            for col in 0..universe.width() {
+                let idx = universe.get_index(row, col);
+                assert_eq!(counts[idx], universe.live_neighbor_count(row, col));
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_matches_reference_on_random_grids() {
+    for seed in 0..4 {
+        let mut universe = random_universe(17, 11, seed);
+        for _ in 0..5 {
+            let expected = reference_tick(&universe);
+            universe.tick();
+            assert_eq!(universe.get_cells(), expected.as_slice());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_default_matches_new() {
+    let universe = Universe::default();
+    assert_eq!(universe.width(), 64);
+    assert_eq!(universe.height(), 64);
+    assert_eq!(universe.get_cells(), Universe::new().get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_subgrid() {
+    let universe = input_spaceship();
+    let sub = universe.subgrid(1, 1, 3, 3).unwrap();
+    assert_eq!(sub.width(), 3);
+    assert_eq!(sub.height(), 3);
+    let mut expected = Universe::new();
+    expected.set_width(3);
+    expected.set_height(3);
+    expected.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(sub.get_cells(), expected.get_cells());
+
+    let clipped = universe.subgrid(4, 4, 10, 10).unwrap();
+    assert_eq!((clipped.width(), clipped.height()), (2, 2));
+    assert!(universe.subgrid(6, 0, 1, 1).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_on_tick_fires_once_per_step() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let sink = seen.clone();
+    let cb = Closure::wrap(Box::new(move |generation: u32| {
+        sink.borrow_mut().push(generation);
+    }) as Box<dyn FnMut(u32)>);
+
+    let mut universe = input_spaceship();
+    universe.tick_n(2);
+    universe.set_on_tick(cb.as_ref().unchecked_ref::<js_sys::Function>().clone());
+    universe.tick_n(3);
+    assert_eq!(*seen.borrow(), vec![3, 4, 5]);
+    assert_eq!(universe.generation(), 5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_on_tick_throwing_callback_does_not_stop_run() {
+    let mut universe = input_spaceship();
+    universe.set_on_tick(js_sys::Function::new_no_args("throw new Error('boom')"));
+    universe.tick_n(4);
+    assert_eq!(universe.generation(), 4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_bit_at() {
+    let mut universe = Universe::new();
+    universe.set_width(40);
+    universe.set_height(2);
+    universe.set_cells(&[(0, 31), (0, 32), (1, 23)]);
+    assert!(!universe.bit_at(30));
+    assert!(universe.bit_at(31));
+    assert!(universe.bit_at(32));
+    assert!(!universe.bit_at(33));
+    assert!(!universe.bit_at(62));
+    assert!(universe.bit_at(63));
+    assert!(!universe.bit_at(64));
+    assert!(!universe.bit_at(80));
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_rle() {
+    let universe =
+        Universe::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+    let mut expected = Universe::new();
+    expected.set_width(3);
+    expected.set_height(3);
+    expected.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_rle_rejects_malformed_input() {
+    let inputs = [
+        "",
+        "#C only a comment",
+        "x = 3",
+        "x = 3, y =",
+        "x = abc, y = 3\n3o!",
+        "x = 0, y = 3\n!",
+        "x = 100000, y = 100000\n!",
+        "x = 3, y = 3\nbob$2bo$3o",
+        "x = 3, y = 3\n4o!",
+        "x = 3, y = 3\n99999999999o!",
+        "x = 3, y = 3\n3$o!",
+        "x = 3, y = 3\nbo3!",
+        "x = 3, y = 3\nbqb!",
+    ];
+    for input in inputs.iter() {
+        assert!(Universe::from_rle(input).is_err(), "accepted {:?}", input);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_rle_never_panics_on_mutated_input() {
+    let valid = b"x = 3, y = 3\nbob$2bo$3o!";
+    let alphabet = b"0123456789bo$!xy=, \n#";
+    let mut state: u64 = 7;
+    for _ in 0..500 {
+        let mut input = valid.to_vec();
+        for _ in 0..4 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let pos = (state >> 33) as usize % input.len();
+            input[pos] = alphabet[(state >> 50) as usize % alphabet.len()];
+        }
+        let _ = Universe::from_rle(&String::from_utf8(input).unwrap());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_alive_neighbors_mask() {
+    let mut universe = Universe::new();
+    universe.set_width(5);
+    universe.set_height(5);
+    // North, east and south-west of (2, 2).
+    universe.set_cells(&[(1, 2), (2, 3), (3, 1)]);
+    assert_eq!(universe.alive_neighbors_mask(2, 2), 0b0010_0101);
+    // Wraps around: (0, 0)'s north-west neighbor is (4, 4).
+    universe.set_cells(&[(4, 4)]);
+    assert_eq!(universe.alive_neighbors_mask(0, 0), 0b1000_0000);
+}
+
+#[wasm_bindgen_test]
+pub fn test_randomize_region_leaves_outside_untouched() {
+    let mut universe = Universe::new();
+    universe.set_width(10);
+    universe.set_height(10);
+    universe.randomize_region(3, 4, 2, 3, 42, 1.0);
+    for row in 0..10 {
+        for col in 0..10 {
+            let inside = (3..5).contains(&row) && (4..7).contains(&col);
+            let cell = universe.get_cells()[universe.get_index(row, col)];
+            assert_eq!(cell == Cell::Alive, inside, "({}, {})", row, col);
+        }
+    }
+
+    let mut clipped = Universe::new();
+    clipped.set_width(4);
+    clipped.set_height(4);
+    clipped.randomize_region(2, 2, 100, 100, 1, 1.0);
+    assert_eq!(
+        clipped
+            .get_cells()
+            .iter()
+            .filter(|&&c| c == Cell::Alive)
+            .count(),
+        4
+    );
+
+    let mut a = Universe::new();
+    let mut b = Universe::new();
+    a.randomize_region(0, 0, 64, 64, 7, 0.5);
+    b.randomize_region(0, 0, 64, 64, 7, 0.5);
+    assert_eq!(a.get_cells(), b.get_cells());
+}
+
+#[cfg(test)]
+pub fn is_mirrored(universe: &Universe, flip_rows: bool, flip_cols: bool) -> bool {
+    let (width, height) = (universe.width(), universe.height());
+    (0..height).all(|row| {
+        (0..width).all(|col| {
+            let mirror_row = if flip_rows { height - 1 - row } else { row };
+            let mirror_col = if flip_cols { width - 1 - col } else { col };
+            universe.get_cells()[universe.get_index(row, col)]
+                == universe.get_cells()[universe.get_index(mirror_row, mirror_col)]
+        })
+    })
+}
+
+#[wasm_bindgen_test]
+pub fn test_make_symmetric() {
+    let mut universe = input_spaceship();
+    let original = universe.as_bool_vec();
+    assert!(!is_mirrored(&universe, false, true));
+    universe.make_symmetric("horizontal").unwrap();
+    assert!(is_mirrored(&universe, false, true));
+    assert!(universe.step_back());
+    assert_eq!(universe.as_bool_vec(), original);
+
+    let mut universe = input_spaceship();
+    universe.make_symmetric("both").unwrap();
+    assert!(is_mirrored(&universe, false, true));
+    assert!(is_mirrored(&universe, true, false));
+
+    assert!(universe.make_symmetric("diagonal").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_changed() {
+    let mut block = Universe::new();
+    block.set_width(6);
+    block.set_height(6);
+    block.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert!(!block.tick_changed());
+
+    let mut blinker = Universe::new();
+    blinker.set_width(5);
+    blinker.set_height(5);
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    assert!(blinker.tick_changed());
+    assert!(blinker.tick_changed());
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_rgba() {
+    let mut universe = Universe::new();
+    universe.set_width(2);
+    universe.set_height(1);
+    universe.set_cells(&[(0, 1)]);
+    assert_eq!(
+        universe.render_rgba(0x1122_33ff, 0xffff_ff00),
+        vec![0xff, 0xff, 0xff, 0x00, 0x11, 0x22, 0x33, 0xff]
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_capture_frames() {
+    let mut universe = input_spaceship();
+    let frames = universe.capture_frames(3, 0x0000_00ff, 0xffff_ffff);
+    assert_eq!(frames.len(), 3 * 6 * 6 * 4);
+    assert_eq!(universe.generation(), 3);
+    let last = &frames[2 * 6 * 6 * 4..];
+    assert_eq!(
+        last,
+        universe.render_rgba(0x0000_00ff, 0xffff_ffff).as_slice()
+    );
+}
+
+#[cfg(test)]
+pub fn glider() -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(3);
+    universe.set_height(3);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    universe
+}
+
+#[wasm_bindgen_test]
+pub fn test_parse_detects_format() {
+    let inputs = [
+        "x = 3, y = 3\nbob$2bo$3o!",
+        "#N Glider\nx = 3, y = 3\nbob$2bo$3o!",
+        "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n",
+        "!Name: Glider\n.O.\n..O\nOOO\n",
+        ".*\n..*\n***",
+        "◻◼◻\n◻◻◼\n◼◼◼\n",
+    ];
+    for input in inputs.iter() {
+        let universe = Universe::parse(input).unwrap();
+        assert_eq!(universe.width(), 3, "{:?}", input);
+        assert_eq!(universe.height(), 3, "{:?}", input);
+        assert_eq!(universe.get_cells(), glider().get_cells(), "{:?}", input);
+    }
+    assert_eq!(
+        Universe::parse(&glider().render()).unwrap().get_cells(),
+        glider().get_cells()
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_parse_rejects_unknown_format() {
+    assert!(Universe::parse("").is_err());
+    assert!(Universe::parse("hello world").is_err());
+    assert!(Universe::parse("#Life 1.06\n1 two\n").is_err());
+    assert!(Universe::parse(".O.\n.X.\n").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_rule_from_numbers() {
+    let mut classic = random_universe(16, 16, 3);
+    let mut explicit = random_universe(16, 16, 3);
+    explicit.set_rule_from_numbers(&[3], &[2, 3]).unwrap();
+    for _ in 0..10 {
+        classic.tick();
+        explicit.tick();
+        assert_eq!(classic.get_cells(), explicit.get_cells());
+    }
+
+    // B36/S23 (HighLife) differs from Life whenever 6 neighbors appear.
+    let mut highlife = Universe::new();
+    highlife.set_width(5);
+    highlife.set_height(5);
+    highlife.set_cells(&[(1, 1), (1, 2), (1, 3), (3, 1), (3, 2), (3, 3)]);
+    highlife.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    highlife.tick();
+    assert_eq!(highlife.get_cells()[highlife.get_index(2, 2)], Cell::Alive);
+
+    assert!(explicit.set_rule_from_numbers(&[9], &[2]).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_activity_map() {
+    let mut universe = Universe::new();
+    universe.set_width(5);
+    universe.set_height(5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.tick_n(4);
+    let activity = universe.activity();
+    // The ends of the blinker flip every generation; the center never does.
+    for &(row, col) in [(2, 1), (2, 3), (1, 2), (3, 2)].iter() {
+        assert_eq!(activity[universe.get_index(row, col)], 4);
+    }
+    assert_eq!(activity[universe.get_index(2, 2)], 0);
+    assert_eq!(activity.iter().sum::<u32>(), 16);
+
+    universe.reset_activity();
+    assert!(universe.activity().iter().all(|&count| count == 0));
+}
+
+#[wasm_bindgen_test]
+pub fn test_contains_pattern() {
+    let mut universe = Universe::new();
+    universe.set_width(8);
+    universe.set_height(8);
+    assert!(!universe.contains_pattern("block").unwrap());
+
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert!(universe.contains_pattern("block").unwrap());
+    assert!(!universe.contains_pattern("blinker").unwrap());
+
+    // A vertical blinker matches the horizontal catalog entry.
+    universe.set_cells(&[(5, 6), (6, 6), (7, 6)]);
+    assert!(universe.contains_pattern("blinker").unwrap());
+
+    // Touching the block makes it part of a larger object.
+    universe.set_cells(&[(4, 4)]);
+    assert!(!universe.contains_pattern("block").unwrap());
+
+    assert!(universe.contains_pattern("spaceship").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_ticks_per_second() {
+    let mut universe = input_spaceship();
+    assert!(universe.set_ticks_per_second(0.0).is_err());
+    assert!(universe.set_ticks_per_second(-5.0).is_err());
+    assert!(universe.set_ticks_per_second(f64::NAN).is_err());
+    assert_eq!(universe.ticks_per_second(), 60.0);
+
+    universe.set_ticks_per_second(10.0).unwrap();
+    assert_eq!(universe.tick_frame(50.0), 0);
+    assert_eq!(universe.tick_frame(60.0), 1);
+    assert_eq!(universe.tick_frame(290.0), 3);
+    assert_eq!(universe.generation(), 4);
+}
+
+#[cfg(test)]
+pub fn sized_universe(width: u32, height: u32) -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(width);
+    universe.set_height(height);
+    universe
+}
+
+#[wasm_bindgen_test]
+pub fn test_empty_universes_are_no_ops() {
+    for &(width, height) in [(0, 0), (0, 5), (5, 0)].iter() {
+        let mut universe = sized_universe(width, height);
+        assert!(universe.is_empty());
+        universe.toggle_cell(0, 0);
+        universe.toggle_cell(3, 3);
+        universe.set_cells(&[(0, 0), (1, 2)]);
+        assert!(!universe.tick_changed());
+        universe.tick_n(3);
+        assert_eq!(universe.generation(), 0);
+        assert_eq!(universe.render(), "");
+        assert!(universe.neighbor_counts().is_empty());
+        assert_eq!(universe.live_neighbor_count(0, 0), 0);
+        assert_eq!(universe.alive_neighbors_mask(0, 0), 0);
+        assert!(universe.render_rgba(0, 0).is_empty());
+        assert!(universe.subgrid(0, 0, 1, 1).is_err());
+        assert!(universe.make_symmetric("both").is_ok());
+        assert!(!universe.contains_pattern("block").unwrap());
+        assert!(!universe.bit_at(0));
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_weighted() {
+    let mut classic = random_universe(12, 10, 5);
+    let mut weighted = random_universe(12, 10, 5);
+    weighted.set_kernel(&[1, 1, 1, 1, 0, 1, 1, 1, 1]);
+    for _ in 0..6 {
+        classic.tick();
+        weighted.tick_weighted();
+        assert_eq!(classic.get_cells(), weighted.get_cells());
+    }
+
+    // Only the northern neighbor counts, with weight 2. Under B2/S2 a lone
+    // cell gives birth to the cell below it and dies itself.
+    let mut universe = sized_universe(3, 3);
+    universe.set_cells(&[(0, 1)]);
+    universe.set_kernel(&[0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    universe.set_rule_from_numbers(&[2], &[2]).unwrap();
+    universe.tick_weighted();
+    let mut expected = sized_universe(3, 3);
+    expected.set_cells(&[(1, 1)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_as_bool_vec() {
+    let universe = random_universe(9, 7, 11);
+    let bytes = universe.as_bool_vec();
+    assert_eq!(bytes.len(), universe.len());
+    for (byte, cell) in bytes.iter().zip(universe.get_cells()) {
+        assert_eq!(*byte == 1, *cell == Cell::Alive);
+        assert!(*byte <= 1);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_dead_boundary_neighbors() {
+    let mut universe = sized_universe(4, 4);
+    universe.set_cells(&[(3, 3), (0, 1)]);
+    assert_eq!(universe.live_neighbor_count(0, 0), 2);
+    universe.set_boundary("dead").unwrap();
+    assert_eq!(universe.live_neighbor_count(0, 0), 1);
+    assert_eq!(universe.alive_neighbors_mask(0, 0), 0b0000_0100);
+    assert_eq!(universe.neighbor_counts()[universe.get_index(0, 0)], 1);
+    assert!(universe.set_boundary("klein").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_escaped_count_glider_into_edge() {
+    let mut torus = sized_universe(8, 8);
+    torus.set_cells(&[(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+    torus.tick_n(40);
+    assert_eq!(torus.escaped_count(), 0);
+
+    let mut universe = sized_universe(8, 8);
+    universe.set_boundary("dead").unwrap();
+    universe.set_cells(&[(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+    // The glider is still in open space for its first few generations.
+    universe.tick_n(4);
+    assert_eq!(universe.escaped_count(), 0);
+    universe.tick_n(36);
+    assert!(universe.escaped_count() > 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_step_back_undoes_edits_and_ticks() {
+    let mut universe = sized_universe(8, 8);
+    universe.set_cells(&[(6, 6), (6, 7)]);
+    let before = universe.get_cells().to_vec();
+
+    universe.insert_pattern("glider", 1, 1).unwrap();
+    assert_ne!(universe.get_cells(), before.as_slice());
+    let with_glider = universe.get_cells().to_vec();
+    universe.tick();
+    assert!(universe.step_back());
+    assert_eq!(universe.get_cells(), with_glider.as_slice());
+    assert_eq!(universe.generation(), 0);
+    assert!(universe.step_back());
+    assert_eq!(universe.get_cells(), before.as_slice());
+
+    universe.clear();
+    assert!(universe.get_cells().iter().all(|&c| c == Cell::Dead));
+    assert!(universe.step_back());
+    universe.randomize(3, 0.5);
+    assert!(universe.step_back());
+    universe.stamp_rle("x = 2, y = 1\n2o!", 0, 0).unwrap();
+    assert!(universe.step_back());
+    assert_eq!(universe.get_cells(), before.as_slice());
+    assert!(!universe.step_back());
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_rle_overwrites_and_clips() {
+    let mut universe = sized_universe(4, 4);
+    universe.set_cells(&[(1, 1), (3, 3)]);
+    universe.stamp_rle("x = 3, y = 2\no$2bo!", 1, 0).unwrap();
+    let mut expected = sized_universe(4, 4);
+    expected.set_cells(&[(1, 0), (2, 2), (3, 3)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    universe.insert_pattern("block", 3, 3).unwrap();
+    assert!(universe.bit_at(universe.get_index(3, 3)));
+    assert!(universe.insert_pattern("nothing", 0, 0).is_err());
+    assert!(universe.stamp_rle("x = 1", 0, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_history_limit() {
+    let mut universe = input_spaceship();
+    universe.set_history_limit(2);
+    universe.tick_n(5);
+    assert!(universe.step_back());
+    assert!(universe.step_back());
+    assert!(!universe.step_back());
+    assert_eq!(universe.generation(), 3);
+
+    universe.set_history_limit(0);
+    universe.tick();
+    assert!(!universe.step_back());
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_svg() {
+    let mut universe = sized_universe(4, 4);
+    universe.insert_pattern("block", 1, 1).unwrap();
+    let svg = universe.to_svg(10, "#000", false);
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains("width=\"40\" height=\"40\""));
+    assert_eq!(svg.matches("<rect").count(), 4);
+    assert!(svg.contains("<rect x=\"10\" y=\"20\" width=\"10\" height=\"10\" fill=\"#000\"/>"));
+
+    let svg = universe.to_svg(10, "\"><script>", true);
+    assert_eq!(svg.matches("<rect").count(), 5);
+    assert!(!svg.contains("<script>"));
+
+    let svg = universe.to_svg(u32::MAX, "#000", false);
+    assert!(svg.contains("width=\"17179869180\""));
+    assert!(svg.contains("<rect x=\"4294967295\" y=\"8589934590\""));
+}
+
+#[wasm_bindgen_test]
+pub fn test_alive_indices() {
+    let universe = random_universe(10, 6, 9);
+    let mut expected = Vec::new();
+    for (idx, cell) in universe.get_cells().iter().enumerate() {
+        if *cell == Cell::Alive {
+            expected.push(idx as u32);
+        }
+    }
+    assert!(!expected.is_empty());
+    assert_eq!(universe.alive_indices(), expected);
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_cells_format() {
+    let text = "!Name: Glider\n!Author: Richard K. Guy\n!\n.O\n..O\nOOO\n";
+    let universe = Universe::from_cells_format(text).unwrap();
+    assert_eq!((universe.width(), universe.height()), (3, 3));
+    assert_eq!(universe.get_cells(), glider().get_cells());
+
+    // Blank lines inside the grid are dead rows.
+    let universe = Universe::from_cells_format("O\n\n.O\n").unwrap();
+    assert_eq!((universe.width(), universe.height()), (2, 3));
+    assert_eq!(universe.alive_indices(), vec![0, 5]);
+
+    assert!(Universe::from_cells_format("!only comments\n").is_err());
+    assert!(Universe::from_cells_format(".O.\nxyz\n").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_quadrant_populations() {
+    let mut universe = sized_universe(10, 10);
+    universe.insert_pattern("glider", 6, 1).unwrap();
+    assert_eq!(universe.quadrant_populations(), vec![0, 0, 5, 0]);
+
+    // On a 5x5 grid the middle row and column belong to the bottom/right.
+    let mut odd = sized_universe(5, 5);
+    odd.set_cells(&[(2, 2), (1, 1), (0, 4)]);
+    assert_eq!(odd.quadrant_populations(), vec![1, 1, 0, 1]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_rgba_with_trails() {
+    let mut universe = sized_universe(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.set_track_ages(true);
+    universe.tick();
+    let end = universe.get_index(2, 1) * 4;
+    let untouched = universe.get_index(0, 0) * 4;
+
+    let (alive, dead) = (0x0000_00ff, 0xffff_ffff);
+    let pixels = universe.render_rgba_with_trails(alive, dead, 3);
+    // The blinker's end cell just died, so it's between the two colors.
+    assert_ne!(&pixels[end..end + 4], &[0xff, 0xff, 0xff, 0xff]);
+    assert_ne!(&pixels[end..end + 4], &[0x00, 0x00, 0x00, 0xff]);
+    assert_eq!(&pixels[untouched..untouched + 4], &[0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(universe.ages()[universe.get_index(2, 1)], 0);
+    assert_eq!(universe.ages()[universe.get_index(2, 2)], 1);
+
+    // Without trails it's the plain rendering.
+    assert_eq!(
+        universe.render_rgba_with_trails(alive, dead, 0),
+        universe.render_rgba(alive, dead)
+    );
+    universe.set_track_ages(false);
+    assert!(universe.ages().is_empty());
+    assert_eq!(
+        universe.render_rgba_with_trails(alive, dead, 3),
+        universe.render_rgba(alive, dead)
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_critters_tick_back_restores_grid() {
+    let mut universe = CrittersUniverse::new(16, 12).unwrap();
+    universe.randomize(21, 0.4);
+    let start = universe.get_cells().to_vec();
+
+    universe.tick();
+    assert_ne!(universe.get_cells(), start.as_slice());
+    universe.tick_back();
+    assert_eq!(universe.get_cells(), start.as_slice());
+
+    for _ in 0..7 {
+        universe.tick();
+    }
+    for _ in 0..7 {
+        universe.tick_back();
+    }
+    assert_eq!(universe.get_cells(), start.as_slice());
+    assert_eq!(universe.generation(), 0);
+
+    // Running backwards first and then forwards is also an identity.
+    universe.tick_back();
+    universe.tick();
+    assert_eq!(universe.get_cells(), start.as_slice());
+}
+
+#[wasm_bindgen_test]
+pub fn test_critters_block_rules() {
+    // Every block is complemented: empty blocks fill up and the lone
+    // cell's block keeps only its other three cells.
+    let mut universe = CrittersUniverse::new(4, 4).unwrap();
+    universe.set_cells(&[(0, 0)]);
+    universe.tick();
+    let alive: Vec<usize> = (0..16)
+        .filter(|&i| universe.get_cells()[i] == Cell::Alive)
+        .collect();
+    assert_eq!(alive, (1..16).collect::<Vec<usize>>());
+
+    // Three live cells are complemented and rotated: the block with only
+    // its north-west cell dead ends up with only its south-east cell alive.
+    let mut universe = CrittersUniverse::new(4, 4).unwrap();
+    universe.set_cells(&[(0, 1), (1, 0), (1, 1)]);
+    universe.tick();
+    assert_eq!(universe.get_cells()[universe.get_index(0, 0)], Cell::Dead);
+    assert_eq!(universe.get_cells()[universe.get_index(1, 1)], Cell::Alive);
+    assert_eq!(universe.get_cells()[universe.get_index(0, 1)], Cell::Dead);
+
+    assert!(CrittersUniverse::new(3, 4).is_err());
+    assert!(CrittersUniverse::new(0, 4).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_from_other() {
+    let mut source = random_universe(12, 9, 4);
+    source.tick_n(3);
+
+    let mut universe = Universe::new();
+    assert_ne!(universe.state_hash(), source.state_hash());
+    universe.set_from_other(&source);
+    assert_eq!(universe.state_hash(), source.state_hash());
+    assert_eq!((universe.width(), universe.height()), (12, 9));
+    assert_eq!(universe.generation(), 3);
+    universe.tick();
+    source.tick();
+    assert_eq!(universe.get_cells(), source.get_cells());
+
+    // Same-sized copies can be undone.
+    let before = universe.state_hash();
+    universe.set_from_other(&random_universe(12, 9, 5));
+    assert_ne!(universe.state_hash(), before);
+    assert!(universe.step_back());
+    assert_eq!(universe.state_hash(), before);
+}
+
+#[wasm_bindgen_test]
+pub fn test_state_hash() {
+    let a = random_universe(8, 8, 1);
+    let mut b = random_universe(8, 8, 1);
+    assert_eq!(a.state_hash(), b.state_hash());
+    b.toggle_cell(0, 0);
+    assert_ne!(a.state_hash(), b.state_hash());
+    // Same cells, different shape.
+    assert_ne!(
+        sized_universe(2, 8).state_hash(),
+        sized_universe(8, 2).state_hash()
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_pattern_library() {
+    let names = PatternLibrary::names();
+    assert!(!names.is_empty());
+    for name in names.iter() {
+        let rle = PatternLibrary::get_rle(name).unwrap();
+        assert!(Universe::from_rle(&rle).is_ok(), "{} has invalid RLE", name);
+    }
+    assert!(PatternLibrary::get_rle("not a pattern").is_none());
+
+    let pulsar = Universe::from_rle(&PatternLibrary::get_rle("pulsar").unwrap()).unwrap();
+    assert_eq!(pulsar.alive_indices().len(), 48);
+    let gun = Universe::from_rle(&PatternLibrary::get_rle("gosper_glider_gun").unwrap()).unwrap();
+    assert_eq!(gun.alive_indices().len(), 36);
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbors_alive_at_least() {
+    let universe = random_universe(9, 9, 2);
+    for row in 0..9 {
+        for col in 0..9 {
+            let count = universe.live_neighbor_count(row, col);
+            for n in 0..=9 {
+                assert_eq!(universe.neighbors_alive_at_least(row, col, n), count >= n);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_row_buffered_tick_matches_reference() {
+    let sizes = [(17, 11), (1, 6), (6, 1), (2, 2), (3, 40)];
+    for &(width, height) in sizes.iter() {
+        for &boundary in ["toroidal", "dead"].iter() {
+            for seed in 0..3 {
+                let mut universe = random_universe(width, height, seed);
+                universe.set_boundary(boundary).unwrap();
+                for _ in 0..8 {
+                    let expected = reference_tick(&universe);
+                    universe.tick();
+                    assert_eq!(
+                        universe.get_cells(),
+                        expected.as_slice(),
+                        "{}x{} {} seed {}",
+                        width,
+                        height,
+                        boundary,
+                        seed
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_generation_at_extinction() {
+    let mut universe = sized_universe(60, 60);
+    universe.insert_pattern("diehard", 25, 25).unwrap();
+    universe.tick_n(129);
+    assert_eq!(universe.generation_at_extinction(), None);
+    universe.tick_n(5);
+    assert_eq!(universe.generation_at_extinction(), Some(130));
+
+    universe.toggle_cell(0, 0);
+    assert_eq!(universe.generation_at_extinction(), None);
+    universe.tick();
+    assert_eq!(universe.generation_at_extinction(), Some(135));
+    universe.set_width(10);
+    assert_eq!(universe.generation_at_extinction(), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_braille() {
+    let mut universe = sized_universe(2, 4);
+    let all: Vec<(u32, u32)> = (0..4).flat_map(|r| (0..2).map(move |c| (r, c))).collect();
+    universe.set_cells(&all);
+    assert_eq!(universe.render_braille(), "\u{28ff}\n");
+
+    // Dot 8 is the bottom-right of a block; edge blocks are padded dead.
+    let mut universe = sized_universe(3, 5);
+    universe.set_cells(&[(3, 1), (4, 2)]);
+    assert_eq!(
+        universe.render_braille(),
+        "\u{2880}\u{2800}\n\u{2800}\u{2801}\n"
+    );
+    assert_eq!(sized_universe(0, 0).render_braille(), "");
+}
+
+#[wasm_bindgen_test]
+pub fn test_memory_bytes() {
+    let mut universe = sized_universe(32, 32);
+    universe.set_history_limit(0);
+    let base = universe.memory_bytes();
+    assert!(base >= 32 * 32 * (1 + 4));
+
+    universe.set_track_ages(true);
+    let with_ages = universe.memory_bytes();
+    assert!(with_ages >= base + 32 * 32 * 4);
+
+    universe.set_history_limit(100);
+    universe.tick_n(50);
+    assert!(universe.memory_bytes() >= with_ages + 50 * 32 * 32);
+    universe.set_history_limit(0);
+    assert!(universe.memory_bytes() < with_ages + 50 * 32 * 32);
+}
+
+#[wasm_bindgen_test]
+pub fn test_row_col_of_index() {
+    let universe = sized_universe(7, 5);
+    for row in 0..5 {
+        for col in 0..7 {
+            let idx = universe.get_index(row, col);
+            assert_eq!(universe.row_col_of_index(idx), vec![row, col]);
+        }
+    }
+    assert!(universe.row_col_of_index(35).is_empty());
+    assert!(sized_universe(0, 3).row_col_of_index(0).is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_dirty_region_tick_matches_full_tick() {
+    for &boundary in ["toroidal", "dead"].iter() {
+        let mut universe = sized_universe(64, 48);
+        universe.set_boundary(boundary).unwrap();
+        universe.set_history_limit(0);
+        universe.set_cells(&[(10, 11), (11, 12), (12, 10), (12, 11), (12, 12)]);
+        universe.insert_pattern("blinker", 30, 20).unwrap();
+        for generation in 0..300 {
+            if generation == 150 {
+                universe.toggle_cell(5, 40);
+                universe.toggle_cell(5, 41);
+                universe.toggle_cell(5, 42);
+            }
+            let expected = reference_tick(&universe);
+            universe.tick();
+            assert_eq!(universe.get_cells(), expected.as_slice(), "{}", boundary);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_alive_probability_mask() {
+    let mut universe = sized_universe(8, 6);
+    universe.set_alive_probability_mask(&[1.0; 48], 7).unwrap();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Alive));
+    universe.set_alive_probability_mask(&[0.0; 48], 7).unwrap();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+
+    // A left-to-right step: only the right half can come alive.
+    let mask: Vec<f64> = (0..48)
+        .map(|idx| if idx % 8 < 4 { 0.0 } else { 1.0 })
+        .collect();
+    universe.set_alive_probability_mask(&mask, 7).unwrap();
+    assert_eq!(universe.quadrant_populations(), vec![0, 12, 0, 12]);
+    assert!(universe.set_alive_probability_mask(&[1.0; 47], 7).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_shift_wraps_around() {
+    let mut universe = sized_universe(5, 4);
+    universe.set_cells(&[(0, 0), (3, 4)]);
+    universe.shift(1, -1);
+    assert_eq!(universe.alive_indices(), vec![3, 9]);
+    universe.shift(-1, 1);
+    assert_eq!(universe.alive_indices(), vec![0, 19]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_recenter_follows_glider() {
+    let mut universe = sized_universe(30, 30);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    universe.set_recenter(true);
+    for _ in 0..200 {
+        universe.tick();
+        let alive = universe.alive_indices();
+        assert_eq!(alive.len(), 5);
+        let rows: u32 = alive.iter().map(|idx| idx / 30).sum();
+        let cols: u32 = alive.iter().map(|idx| idx % 30).sum();
+        assert!((rows as f64 / 5.0 - 15.0).abs() <= 1.0);
+        assert!((cols as f64 / 5.0 - 15.0).abs() <= 1.0);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_compare_rules() {
+    let universe = random_universe(24, 24, 3);
+    let before = universe.get_cells().to_vec();
+    assert_eq!(universe.compare_rules("B3/S23", "B3/S23", 40).unwrap(), 0);
+    assert_eq!(universe.compare_rules("B3/S23", "s23/b3", 40).unwrap(), 0);
+    assert_eq!(universe.compare_rules("B3/S23", "23/3", 40).unwrap(), 0);
+    assert!(universe.compare_rules("B3/S23", "B36/S23", 40).unwrap() > 0);
+    assert!(universe.compare_rules("B3/S23", "B3/S9", 1).is_err());
+    assert!(universe.compare_rules("life", "B3/S23", 1).is_err());
+    assert_eq!(universe.get_cells(), before.as_slice());
+    assert_eq!(universe.generation(), 0);
+
+    // Without survival the blinker's center dies, but both rules give
+    // birth to the same two cells above and below it.
+    let mut blinker = sized_universe(5, 5);
+    blinker.insert_pattern("blinker", 1, 1).unwrap();
+    assert_eq!(blinker.compare_rules("B3/S23", "B3/S", 1).unwrap(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_version_tracks_reallocation() {
+    let mut universe = random_universe(16, 16, 5);
+    let (ptr, version) = (universe.cells(), universe.cells_version());
+    universe.tick_n(5);
+    universe.step_back();
+    universe.toggle_cell(3, 3);
+    universe.shift(1, 1);
+    assert_eq!(universe.cells(), ptr);
+    assert_eq!(universe.cells_version(), version);
+
+    universe.set_width(20);
+    assert_ne!(universe.cells_version(), version);
+    let version = universe.cells_version();
+    universe.set_height(20);
+    assert_ne!(universe.cells_version(), version);
+}
+
+#[wasm_bindgen_test]
+pub fn test_apply_noise() {
+    let mut noisy = random_universe(20, 20, 9);
+    let mut clean = random_universe(20, 20, 9);
+    for seed in 0..10 {
+        noisy.tick();
+        noisy.apply_noise(0.0, seed);
+        clean.tick();
+    }
+    assert_eq!(noisy.get_cells(), clean.get_cells());
+
+    noisy.apply_noise(1.0, 0);
+    let mut pairs = noisy.get_cells().iter().zip(clean.get_cells().iter());
+    assert!(pairs.all(|(a, b)| a != b));
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_ascii_grid() {
+    let mut universe = sized_universe(2, 2);
+    universe.set_cells(&[(0, 1), (1, 0)]);
+    assert_eq!(universe.to_ascii_grid('#', '.', false), ".#\n#.");
+    assert_eq!(universe.to_ascii_grid('#', '.', true), ".#\n#.\n");
+    assert_eq!(universe.to_ascii_grid('◼', '◻', true), universe.render());
+    assert_eq!(sized_universe(0, 0).to_ascii_grid('#', '.', true), "");
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_matches_rescan() {
+    let rescan = |universe: &Universe| {
+        universe
+            .get_cells()
+            .iter()
+            .filter(|&&cell| cell == Cell::Alive)
+            .count() as u32
+    };
+    let mut universe = random_universe(24, 20, 11);
+    assert_eq!(universe.population(), rescan(&universe));
+    let other = random_universe(24, 20, 12);
+    let mut state: u64 = 99;
+    for step in 0..400 {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let (row, col) = ((state >> 40) as u32 % 24, (state >> 20) as u32 % 28);
+        match (state >> 59) % 16 {
+            0 | 1 | 2 => universe.tick(),
+            3 => universe.toggle_cell(row, col),
+            4 => universe.set_cell(row, col, state % 3 == 1),
+            5 => universe.invert(),
+            6 => universe.clear(),
+            7 => universe.randomize_region(row, col, 6, 6, state, 0.5),
+            8 => universe.insert_pattern("glider", row, col).unwrap(),
+            9 => {
+                universe.step_back();
+            }
+            10 => universe.shift(row as i32, -(col as i32)),
+            11 => universe.apply_noise(0.1, state),
+            12 => universe.make_symmetric("both").unwrap(),
+            13 => universe.set_cells(&[(row, col), (col, row)]),
+            14 => universe.tick_weighted(),
+            _ => universe.set_from_other(&other),
+        }
+        assert_eq!(universe.population(), rescan(&universe), "step {}", step);
+    }
+    universe.set_width(7);
+    assert_eq!(universe.population(), 0);
+    let sub = other.subgrid(2, 2, 10, 10).unwrap();
+    assert_eq!(sub.population(), rescan(&sub));
+    assert_eq!(Universe::new().population(), rescan(&Universe::new()));
+}
+
+#[wasm_bindgen_test]
+pub fn test_glider_count_escaped_per_edge() {
+    // A glider heading down and to the right, on a grid wide enough that it
+    // reaches the bottom edge long before the right one. It wrecks itself
+    // into a block against the edge.
+    let mut universe = sized_universe(40, 12);
+    universe.set_boundary("dead").unwrap();
+    universe.set_cells(&[(2, 3), (3, 4), (4, 2), (4, 3), (4, 4)]);
+    universe.tick_n(80);
+    assert_eq!(universe.glider_count_escaped_per_edge(), vec![0, 0, 1, 0]);
+
+    // The same glider rotated to head up and to the left hits the top.
+    let mut universe = sized_universe(40, 12);
+    universe.set_boundary("dead").unwrap();
+    universe.set_cells(&[(9, 31), (8, 30), (7, 32), (7, 31), (7, 30)]);
+    universe.tick_n(80);
+    assert_eq!(universe.glider_count_escaped_per_edge(), vec![1, 0, 0, 0]);
+    assert_eq!(universe.escaped_count(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_save_load_resumes_identically() {
+    let mut original = random_universe(19, 13, 21);
+    original.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    original.set_boundary("dead").unwrap();
+    original.tick_n(7);
+    let mut restored = Universe::load(&original.save()).unwrap();
+    assert_eq!(restored.save(), original.save());
+    assert_eq!(restored.population(), original.population());
+    for _ in 0..20 {
+        original.tick();
+        restored.tick();
+        assert_eq!(restored.get_cells(), original.get_cells());
+    }
+    assert_eq!(restored.generation(), 27);
+    assert_eq!((restored.width(), restored.height()), (19, 13));
+
+    assert!(Universe::load("{}").is_err());
+    let padded = original.save().replace("\"cells\":[", "\"cells\":[0,");
+    assert!(Universe::load(&padded).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_randomize_symmetric() {
+    for &(width, height) in [(10, 7), (9, 8)].iter() {
+        let mut universe = sized_universe(width, height);
+        universe.randomize_symmetric(1, 0.5, "horizontal").unwrap();
+        assert!(is_mirrored(&universe, false, true));
+        let left_right = universe.get_cells().to_vec();
+        assert!(universe.population() > 0);
+
+        universe.randomize_symmetric(1, 0.5, "vertical").unwrap();
+        assert!(is_mirrored(&universe, true, false));
+        assert!(!is_mirrored(&universe, false, true));
+
+        universe.randomize_symmetric(2, 0.5, "both").unwrap();
+        assert!(is_mirrored(&universe, true, false));
+        assert!(is_mirrored(&universe, false, true));
+
+        universe.randomize_symmetric(1, 0.5, "horizontal").unwrap();
+        assert_eq!(universe.get_cells(), left_right.as_slice());
+    }
+    assert!(sized_universe(4, 4)
+        .randomize_symmetric(1, 0.5, "diagonal")
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_write_cells_round_trips_as_bool_vec() {
+    let source = random_universe(11, 9, 4);
+    let mut universe = sized_universe(11, 9);
+    universe.write_cells(&source.as_bool_vec()).unwrap();
+    assert_eq!(universe.get_cells(), source.get_cells());
+    assert_eq!(universe.population(), source.population());
+
+    let bytes: Vec<u8> = (0..99).map(|idx| (idx % 3) as u8 * 100).collect();
+    universe.write_cells(&bytes).unwrap();
+    let expected: Vec<u8> = bytes.iter().map(|&byte| (byte != 0) as u8).collect();
+    assert_eq!(universe.as_bool_vec(), expected);
+    assert!(universe.write_cells(&bytes[1..]).is_err());
+    assert!(universe.step_back());
+    assert_eq!(universe.get_cells(), source.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_times_use_performance_now() {
+    let mut universe = sized_universe(32, 32);
+    universe.set_tick_timing(4);
+    universe.tick_n(6);
+    let times = universe.tick_times_ms();
+    assert_eq!(times.len(), 4);
+    assert!(times.iter().all(|&ms| ms.is_finite() && ms >= 0.0));
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_patches_cover_changed_cells() {
+    let mut universe = sized_universe(5, 5);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.tick();
+    assert_eq!(universe.changed_cells(), vec![7, 11, 13, 17]);
+    let patches = universe.render_patches(0xffffffff, 0x000000ff);
+    assert_eq!(patches.len(), universe.changed_cells().len() * 3);
+    assert_eq!(&patches[..3], &[1, 2, 0xffffffff]);
+    assert_eq!(&patches[3..6], &[2, 1, 0x000000ff]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_torus_offset_shifts_wrapped_neighbors() {
+    const SOUTH: u8 = 1 << 4;
+    let mut universe = sized_universe(4, 4);
+    universe.set_cells(&[(0, 2)]);
+    assert_eq!(universe.alive_neighbors_mask(3, 2) & SOUTH, SOUTH);
+    assert_eq!(universe.alive_neighbors_mask(3, 1) & SOUTH, 0);
+
+    universe.set_torus_offset(0, 1);
+    assert_eq!(universe.alive_neighbors_mask(3, 2) & SOUTH, 0);
+    assert_eq!(universe.alive_neighbors_mask(3, 1) & SOUTH, SOUTH);
+
+    let mut universe = random_universe(9, 7, 11);
+    universe.set_torus_offset(2, 3);
+    for _ in 0..5 {
+        let expected = reference_tick(&universe);
+        universe.tick();
+        assert_eq!(universe.get_cells(), &expected[..]);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_classify_still_lifes() {
+    let mut universe = sized_universe(12, 10);
+    universe.insert_pattern("block", 1, 1).unwrap();
+    universe.insert_pattern("beehive", 5, 6).unwrap();
+    assert_eq!(universe.classify_still_lifes(), vec!["block", "beehive"]);
+
+    universe.insert_pattern("glider", 1, 7).unwrap();
+    assert_eq!(
+        universe.classify_still_lifes(),
+        vec!["block", "unknown", "beehive"]
+    );
+
+    // A blinker split across the wrap is still one object.
+    let mut universe = sized_universe(6, 6);
+    universe.set_cells(&[(5, 2), (0, 2), (1, 2)]);
+    assert_eq!(universe.classify_still_lifes(), vec!["blinker"]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_traced_calls_back_once_per_cell() {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let sink = calls.clone();
+    let cb = Closure::wrap(
+        Box::new(move |row: u32, col: u32, old: u8, count: u8, new: u8| {
+            sink.borrow_mut().push((row, col, old, count, new));
+        }) as Box<dyn FnMut(u32, u32, u8, u8, u8)>,
+    );
+
+    let mut universe = sized_universe(7, 5);
+    universe.set_cells(&[(2, 2), (2, 3), (2, 4)]);
+    universe.tick_traced(cb.as_ref().unchecked_ref());
+    let calls = calls.borrow();
+    assert_eq!(calls.len(), (universe.width() * universe.height()) as usize);
+    assert!(calls.contains(&(2, 2, 1, 1, 0)));
+    assert!(calls.contains(&(1, 3, 0, 3, 1)));
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(universe.population(), 3);
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_derivative() {
+    let mut universe = sized_universe(8, 8);
+    universe.set_cells(&[(1, 1), (4, 4), (4, 5)]);
+    assert_eq!(universe.population_derivative(), 0);
+    universe.tick();
+    assert_eq!(universe.population_derivative(), -3);
+
+    let mut universe = sized_universe(60, 40);
+    universe.insert_pattern("gosper_glider_gun", 1, 1).unwrap();
+    let start = universe.population() as i32;
+    let mut growth = 0;
+    for _ in 0..30 {
+        universe.tick();
+        growth += universe.population_derivative();
+    }
+    assert_eq!(growth, universe.population() as i32 - start);
+    assert!(growth > 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_configuration_getters() {
+    let mut universe = sized_universe(8, 8);
+    assert_eq!(universe.rule_notation(), "B3/S23");
+    assert_eq!(universe.boundary_name(), "toroidal");
+    assert_eq!(universe.torus_offset(), vec![0, 0]);
+
+    universe.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    universe.set_boundary("dead").unwrap();
+    universe.set_torus_offset(1, -2);
+    assert_eq!(universe.rule_notation(), "B36/S23");
+    assert_eq!(universe.boundary_name(), "dead");
+    assert_eq!(universe.torus_offset(), vec![1, -2]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_region_is_quiescent() {
+    let mut universe = sized_universe(16, 8);
+    universe.insert_pattern("block", 2, 2).unwrap();
+    universe.insert_pattern("blinker", 3, 10).unwrap();
+    assert!(universe.region_is_quiescent(0, 0, 6, 6));
+    assert!(!universe.region_is_quiescent(0, 8, 7, 7));
+    // The blinker's cells lie on the rectangle's border, where they
+    // aren't checked.
+    assert!(universe.region_is_quiescent(3, 10, 1, 3));
+    assert!(universe.region_is_quiescent(0, 0, 100, 5));
+}
+
+#[wasm_bindgen_test]
+pub fn test_csv_round_trip() {
+    let mut universe = sized_universe(5, 3);
+    universe.set_cells(&[(0, 0), (1, 2), (2, 4)]);
+    let csv = universe.export_csv();
+    assert_eq!(csv, "1,0,0,0,0\n0,0,1,0,0\n0,0,0,0,1\n");
+    let loaded = Universe::from_csv(&csv).unwrap();
+    assert_eq!((loaded.width(), loaded.height()), (5, 3));
+    assert_eq!(loaded.get_cells(), universe.get_cells());
+
+    assert!(Universe::from_csv("1,0\n0\n").is_err());
+    assert!(Universe::from_csv("1,2\n").is_err());
+    assert!(Universe::from_csv("").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_step_and_detect_cycle() {
+    let mut universe = sized_universe(19, 19);
+    universe.insert_pattern("pulsar", 3, 3).unwrap();
+    assert_eq!(universe.step_and_detect_cycle(10), Some(3));
+    assert_eq!(universe.generation(), 3);
+
+    let mut universe = sized_universe(19, 19);
+    universe.insert_pattern("pulsar", 3, 3).unwrap();
+    assert_eq!(universe.step_and_detect_cycle(2), None);
+
+    let mut universe = sized_universe(8, 8);
+    universe.insert_pattern("block", 3, 3).unwrap();
+    assert_eq!(universe.step_and_detect_cycle(5), Some(1));
+}
+
+#[wasm_bindgen_test]
+pub fn test_fit_window() {
+    let mut universe = sized_universe(40, 30);
+    universe.insert_pattern("glider", 26, 36).unwrap();
+    // Centering the glider would push the viewport past the bottom-right
+    // corner, so it's clamped against the edges.
+    assert_eq!(universe.fit_window(10, 8), vec![22, 30]);
+
+    universe.clear();
+    universe.insert_pattern("block", 14, 19).unwrap();
+    assert_eq!(universe.fit_window(10, 8), vec![11, 15]);
+    assert_eq!(universe.fit_window(100, 100), vec![0, 0]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_ticks_to_period() {
+    // The lone cell dies on the first tick, leaving just the blinker.
+    let mut universe = sized_universe(10, 10);
+    universe.set_cells(&[(1, 1), (5, 4), (5, 5), (5, 6)]);
+    assert_eq!(universe.ticks_to_period(20), vec![1, 2]);
+    assert_eq!(universe.generation(), 3);
+
+    let mut universe = sized_universe(40, 40);
+    universe.insert_pattern("r_pentomino", 18, 18).unwrap();
+    assert!(universe.ticks_to_period(10).is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_blend_universes() {
+    let a = random_universe(12, 9, 5);
+    let b = random_universe(12, 9, 6);
+    let xor = Universe::blend_universes(&a, &a, "xor").unwrap();
+    assert_eq!(xor.population(), 0);
+
+    let or = Universe::blend_universes(&a, &b, "or").unwrap();
+    let and = Universe::blend_universes(&a, &b, "and").unwrap();
+    let andnot = Universe::blend_universes(&a, &b, "andnot").unwrap();
+    assert_eq!(
+        or.population() + and.population(),
+        a.population() + b.population()
+    );
+    assert_eq!(andnot.population() + and.population(), a.population());
+
+    assert!(Universe::blend_universes(&a, &sized_universe(9, 12), "or").is_err());
+    assert!(Universe::blend_universes(&a, &b, "nand").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_rgba_scaled() {
+    let mut universe = sized_universe(3, 2);
+    universe.set_cells(&[(1, 1)]);
+    let (alive, dead) = (0x11223344, 0xaabbccdd);
+    let pixels = universe.render_rgba_scaled(2, alive, dead).unwrap();
+    assert_eq!(pixels.len(), universe.render_rgba(alive, dead).len() * 4);
+
+    // The image is 6 pixels wide; the live cell covers rows 2-3, columns 2-3.
+    for y in 0..4 {
+        for x in 0..6 {
+            let start = (y * 6 + x) * 4;
+            let expected = if (2..4).contains(&y) && (2..4).contains(&x) {
+                alive
+            } else {
+                dead
+            };
+            assert_eq!(&pixels[start..start + 4], &expected.to_be_bytes());
+        }
+    }
+    assert_eq!(
+        universe.render_rgba_scaled(1, alive, dead).unwrap(),
+        universe.render_rgba(alive, dead)
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_histogram() {
+    let mut universe = sized_universe(6, 6);
+    universe.insert_pattern("block", 2, 2).unwrap();
+    let histogram = universe.neighbor_histogram();
+    assert_eq!(histogram.len(), 9);
+    assert_eq!(histogram.iter().sum::<u32>() as usize, universe.len());
+    // Block cells have 3 live neighbors, the 8 cells beside its edges 2,
+    // the 4 cells off its corners 1 and everything else none.
+    assert_eq!(histogram, vec![20, 4, 8, 4, 0, 0, 0, 0, 0]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_image_data() {
+    #[rustfmt::skip]
+    let rgba = [
+        255, 255, 255, 255,   0, 0, 0, 255,
+        200,  40,  40, 255,  40, 200, 40, 0,
+    ];
+    let universe = Universe::from_image_data(2, 2, &rgba, 100).unwrap();
+    assert_eq!((universe.width(), universe.height()), (2, 2));
+    assert_eq!(universe.as_bool_vec(), vec![1, 0, 0, 1]);
+    let universe = Universe::from_image_data(2, 2, &rgba, 50).unwrap();
+    assert_eq!(universe.as_bool_vec(), vec![1, 0, 1, 1]);
+    assert!(Universe::from_image_data(2, 2, &rgba[..12], 100).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_edge_cells() {
+    let mut universe = sized_universe(4, 4);
+    universe.set_cells(&[(1, 1)]);
+    universe.set_edge_cells(true);
+    assert_eq!(universe.population(), 13);
+    assert_eq!(universe.as_bool_vec()[5..7], [1, 0]);
+    universe.set_cell(1, 1, false);
+    assert_eq!(universe.population(), 12);
+    universe.set_edge_cells(false);
+    assert_eq!(universe.population(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_generations_into_history() {
+    let mut universe = random_universe(16, 16, 9);
+    let mut expected = random_universe(16, 16, 9);
+    universe.tick_generations_into_history(5);
+    assert_eq!(universe.snapshot_count(), 6);
+    assert_eq!(universe.generation(), 5);
+
+    expected.tick_n(3);
+    let third = universe.snapshot(3).unwrap();
+    assert_eq!(third.get_cells(), expected.get_cells());
+    assert_eq!(third.generation(), 3);
+    assert_eq!(third.population(), expected.population());
+    assert!(universe.snapshot(6).is_none());
+
+    universe.set_width(8);
+    assert_eq!(universe.snapshot_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_cell_checked() {
+    let mut universe = sized_universe(4, 3);
+    universe.set_cell_checked(2, 3, true).unwrap();
+    assert!(universe.bit_at(11));
+    assert!(universe.set_cell_checked(3, 0, true).is_err());
+    assert!(universe.set_cell_checked(0, 4, true).is_err());
+    assert_eq!(universe.population(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_trace_glider_path() {
+    let mut universe = sized_universe(16, 16);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    let path = universe.trace_glider_path(8);
+    assert_eq!(path.len(), 18);
+    for period in 0..2 {
+        let (start, end) = (period * 8, period * 8 + 8);
+        assert!((path[end] - path[start] - 1.0).abs() < 1e-9);
+        assert!((path[end + 1] - path[start + 1] - 1.0).abs() < 1e-9);
+    }
+
+    let mut empty = sized_universe(4, 4);
+    assert!(empty.trace_glider_path(10).is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_rotate_and_transpose() {
+    let mut universe = random_universe(7, 4, 17);
+    let original = universe.as_bool_vec();
+
+    universe.rotate_cw();
+    assert_eq!((universe.width(), universe.height()), (4, 7));
+    assert_ne!(universe.as_bool_vec(), original);
+    universe.rotate_ccw();
+    assert_eq!((universe.width(), universe.height()), (7, 4));
+    assert_eq!(universe.as_bool_vec(), original);
+
+    let mut corner = sized_universe(3, 2);
+    corner.set_cells(&[(0, 2)]);
+    corner.rotate_cw();
+    assert!(corner.bit_at(corner.get_index(2, 1)));
+    corner.rotate_ccw();
+    corner.transpose();
+    assert!(corner.bit_at(corner.get_index(2, 0)));
+
+    universe.transpose();
+    assert_eq!((universe.width(), universe.height()), (4, 7));
+    universe.transpose();
+    assert_eq!(universe.as_bool_vec(), original);
+}
+
+#[wasm_bindgen_test]
+pub fn test_count_births_deaths_last_tick() {
+    let mut universe = sized_universe(5, 5);
+    assert_eq!(universe.count_births_deaths_last_tick(), vec![0, 0]);
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    for _ in 0..4 {
+        universe.tick();
+        assert_eq!(universe.count_births_deaths_last_tick(), vec![2, 2]);
+    }
+
+    let mut glider = sized_universe(8, 8);
+    glider.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    glider.tick();
+    let counts = glider.count_births_deaths_last_tick();
+    assert_eq!(counts[0] + counts[1], glider.changed_cells().len() as u32);
+    assert_eq!(counts[0] - counts[1], 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_seed_from_bytes() {
+    let mut first = sized_universe(16, 16);
+    let mut second = sized_universe(16, 16);
+    first.seed_from_bytes(b"rautio");
+    second.seed_from_bytes(b"rautio");
+    assert_eq!(first.as_bool_vec(), second.as_bool_vec());
+    assert!(first.population() > 0);
+
+    second.seed_from_bytes(b"rautiO");
+    assert_ne!(first.as_bool_vec(), second.as_bool_vec());
+    second.seed_from_bytes(b"");
+    assert_ne!(first.as_bool_vec(), second.as_bool_vec());
+}
+
+#[wasm_bindgen_test]
+pub fn test_is_within_bounds() {
+    let universe = sized_universe(5, 3);
+    assert!(universe.is_within_bounds(0, 0));
+    assert!(universe.is_within_bounds(2, 4));
+    assert!(!universe.is_within_bounds(3, 0));
+    assert!(!universe.is_within_bounds(0, 5));
+    assert!(!universe.is_within_bounds(u32::MAX, u32::MAX));
+    assert!(!sized_universe(0, 0).is_within_bounds(0, 0));
+}
+
+#[wasm_bindgen_test]
+pub fn test_merge_history_into_rle_animation() {
+    let universe = sized_universe(0, 0);
+    assert_eq!(universe.merge_history_into_rle_animation(), "");
+
+    let mut universe = sized_universe(8, 6);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    universe.tick_generations_into_history(5);
+    let animation = universe.merge_history_into_rle_animation();
+    assert_eq!(
+        animation.matches("#C generation ").count(),
+        universe.snapshot_count()
+    );
+    assert!(animation.starts_with("#C generation 0\nx = 8, y = 6, rule = B3/S23\nbo$2bo$3o!\n"));
+
+    let frames: Vec<&str> = animation.split("#C generation ").skip(1).collect();
+    for (index, frame) in frames.iter().enumerate() {
+        let (generation, rle) = frame.split_at(frame.find('\n').unwrap());
+        assert_eq!(generation, index.to_string());
+        let parsed = Universe::from_rle(rle).unwrap();
+        let recorded = universe.snapshot(index).unwrap();
+        assert_eq!(parsed.as_bool_vec(), recorded.as_bool_vec());
+    }
+
+    // Frames keep a non-Life rule, so each one ticks into the next.
+    let mut universe = random_universe(10, 8, 3);
+    universe.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    universe.tick_generations_into_history(4);
+    let animation = universe.merge_history_into_rle_animation();
+    let frames: Vec<&str> = animation
+        .split("#C generation ")
+        .skip(1)
+        .map(|frame| &frame[frame.find('\n').unwrap()..])
+        .collect();
+    assert_eq!(frames.len(), 5);
+    for pair in frames.windows(2) {
+        let mut next = Universe::from_rle(pair[0]).unwrap();
+        assert_eq!(next.rule_notation(), "B36/S23");
+        next.tick();
+        let expected = Universe::from_rle(pair[1]).unwrap();
+        assert_eq!(next.as_bool_vec(), expected.as_bool_vec());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_apply_kernel_convolution_debug() {
+    let mut universe = random_universe(9, 7, 5);
+    let counts: Vec<i32> = universe
+        .neighbor_counts()
+        .into_iter()
+        .map(i32::from)
+        .collect();
+    assert_eq!(universe.apply_kernel_convolution_debug(), counts);
+
+    universe.set_kernel(&[0, 0, 0, 0, 0, -1, 0, 0, 0]);
+    let sums = universe.apply_kernel_convolution_debug();
+    for row in 0..7 {
+        for col in 0..9 {
+            let right = universe.get_index(row, (col + 1) % 9);
+            let expected = if universe.bit_at(right) { -1 } else { 0 };
+            assert_eq!(sums[universe.get_index(row, col)], expected);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_diff_rle() {
+    let mut universe = sized_universe(3, 2);
+    universe.set_cells(&[(0, 0), (1, 2)]);
+    let mut other = sized_universe(3, 2);
+    other.set_cells(&[(0, 0), (1, 1)]);
+    assert_eq!(universe.diff_rle(&other), "===\n=-+\n");
+    assert_eq!(other.diff_rle(&universe), "===\n=+-\n");
+    assert_eq!(universe.diff_rle(&universe), "===\n===\n");
+
+    let wider = sized_universe(4, 1);
+    assert_eq!(universe.diff_rle(&wider), "+===\n==+=\n");
+}
+
+#[wasm_bindgen_test]
+pub fn test_prepopulate_common_oscillators() {
+    let mut universe = Universe::prepopulate_common_oscillators();
+    let expected: u32 = ["pulsar", "pentadecathlon", "toad", "beacon"]
+        .iter()
+        .map(|name| {
+            let mut alone = sized_universe(16, 16);
+            alone.insert_pattern(name, 0, 0).unwrap();
+            alone.population()
+        })
+        .sum();
+    assert_eq!(universe.population(), expected);
+
+    let start = universe.as_bool_vec();
+    universe.tick_n(15);
+    assert_ne!(universe.as_bool_vec(), start);
+    universe.tick_n(15);
+    assert_eq!(universe.as_bool_vec(), start);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_isotropic_rule_reproduces_life() {
+    for &notation in [
+        "B3/S23",
+        "B3cekainyqjr/S2cekain3cekainyqjr",
+        "B3-c3c/S2-a2a3",
+    ]
+    .iter()
+    {
+        let mut isotropic = random_universe(24, 18, 31);
+        let mut life = random_universe(24, 18, 31);
+        for _ in 0..10 {
+            isotropic.tick_isotropic_rule(notation).unwrap();
+            life.tick();
+            assert_eq!(isotropic.get_cells(), life.get_cells());
+        }
+        assert_eq!(isotropic.rule_notation(), "B3/S23");
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_auto_resize_on_edge_contact() {
+    let mut universe = sized_universe(10, 10);
+    universe.set_boundary("dead").unwrap();
+    universe.set_cells(&[(3, 4), (4, 5), (5, 3), (5, 4), (5, 5)]);
+    universe.tick_n(4);
+    assert_eq!(universe.width(), 10);
+
+    universe.auto_resize_on_edge_contact(2);
+    for _ in 0..40 {
+        universe.tick();
+        assert_eq!(universe.population(), 5);
+        let alive: Vec<u32> = universe.alive_indices();
+        for index in alive {
+            let position = universe.row_col_of_index(index as usize);
+            assert!(position[0] + 2 < universe.height());
+            assert!(position[1] + 2 < universe.width());
+        }
+    }
+    assert!(universe.width() > 10 && universe.height() > 10);
+    assert_eq!(universe.width(), universe.height());
+
+    let mut grown = sized_universe(3, 2);
+    grown.set_cells(&[(0, 0), (1, 2)]);
+    grown.grow(1);
+    assert_eq!((grown.width(), grown.height()), (5, 4));
+    assert_eq!(grown.alive_indices(), vec![6, 13]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_at() {
+    let mut universe = sized_universe(8, 8);
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (5, 5)]);
+    assert_eq!(universe.population_at(0), Some(4));
+    assert_eq!(universe.population_at(1), None);
+
+    universe.tick_n(3);
+    assert_eq!(universe.population_at(0), Some(4));
+    assert_eq!(universe.population_at(1), Some(4));
+    assert_eq!(universe.population_at(3), Some(universe.population()));
+    assert_eq!(universe.population_at(4), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_clear_configuration() {
+    let mut universe = random_universe(12, 9, 4);
+    universe.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    universe.set_boundary("dead").unwrap();
+    universe.set_torus_offset(1, 2);
+    universe.set_kernel(&[0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    let cells = universe.as_bool_vec();
+
+    universe.clear_configuration();
+    assert_eq!(universe.rule_notation(), "B3/S23");
+    assert_eq!(universe.boundary_name(), "toroidal");
+    assert_eq!(universe.torus_offset(), vec![0, 0]);
+    assert_eq!(universe.as_bool_vec(), cells);
+    assert_eq!(
+        universe.apply_kernel_convolution_debug(),
+        universe
+            .neighbor_counts()
+            .into_iter()
+            .map(i32::from)
+            .collect::<Vec<_>>()
+    );
+
+    let mut life = random_universe(12, 9, 4);
+    universe.tick_n(5);
+    life.tick_n(5);
+    assert_eq!(universe.get_cells(), life.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_detect_spaceships() {
+    let mut glider = sized_universe(16, 16);
+    glider.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(glider.detect_spaceships(8), 1);
+    assert_eq!(glider.detect_spaceships(3), 0);
+    assert_eq!(glider.generation(), 0);
+
+    let mut block = sized_universe(16, 16);
+    block.set_cells(&[(5, 5), (5, 6), (6, 5), (6, 6)]);
+    assert_eq!(block.detect_spaceships(8), 0);
+
+    let mut scene = sized_universe(32, 32);
+    scene.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    scene.set_cells(&[(10, 10), (10, 11), (11, 10), (11, 11)]);
+    scene.set_cells(&[(20, 4), (20, 5), (20, 6)]);
+    scene.set_cells(&[(24, 21), (25, 20), (26, 20), (26, 21), (26, 22)]);
+    assert_eq!(scene.detect_spaceships(8), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_lif_105_round_trip() {
+    let mut universe = sized_universe(9, 8);
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    universe.set_cells(&[(6, 7), (6, 8), (7, 7), (7, 8)]);
+    let text = universe.to_lif_105();
+    assert_eq!(
+        text,
+        "#Life 1.05\n#N\n#P -4 -4\n.*\n..*\n***\n#P 3 2\n**\n**\n"
+    );
+    let loaded = Universe::from_lif_105(&text).unwrap();
+    assert_eq!((loaded.width(), loaded.height()), (9, 8));
+    assert_eq!(loaded.as_bool_vec(), universe.as_bool_vec());
+    assert_eq!(
+        Universe::parse(&text).unwrap().as_bool_vec(),
+        universe.as_bool_vec()
+    );
+
+    // Overlapping blocks are combined.
+    let overlapping = Universe::from_lif_105("#Life 1.05\n#P 0 0\n*.\n#P 1 -1\n.\n*\n").unwrap();
+    assert_eq!((overlapping.width(), overlapping.height()), (2, 1));
+    assert_eq!(overlapping.population(), 2);
+
+    universe.set_rule_from_numbers(&[3, 6], &[2, 3]).unwrap();
+    assert!(universe.to_lif_105().starts_with("#Life 1.05\n#R 23/36\n"));
+}
+
+#[wasm_bindgen_test]
+pub fn test_random_walk_seed() {
+    let mut first = random_universe(20, 20, 3);
+    let mut second = sized_universe(20, 20);
+    first.random_walk_seed(60, 9);
+    second.random_walk_seed(60, 9);
+    assert_eq!(first.as_bool_vec(), second.as_bool_vec());
+    assert!(first.population() > 1 && first.population() <= 61);
+    assert!(first.bit_at(first.get_index(10, 10)));
+    assert_eq!(first.classify_still_lifes().len(), 1);
+
+    second.random_walk_seed(60, 10);
+    assert_ne!(first.as_bool_vec(), second.as_bool_vec());
+
+    let mut walled = sized_universe(5, 5);
+    walled.set_boundary("dead").unwrap();
+    walled.random_walk_seed(500, 1);
+    assert_eq!(walled.population(), 25);
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_packed_u32_rows() {
+    let mut universe = sized_universe(32, 2);
+    universe.set_cells(&(0..32).map(|col| (0, col)).collect::<Vec<_>>());
+    assert_eq!(universe.to_packed_u32_rows(), vec![0xFFFF_FFFF, 0]);
+
+    let mut wide = sized_universe(33, 3);
+    wide.set_cells(&[(0, 0), (1, 31), (1, 32), (2, 5)]);
+    assert_eq!(wide.to_packed_u32_rows(), vec![1, 0, 1 << 31, 1, 1 << 5, 0]);
+    assert!(sized_universe(0, 0).to_packed_u32_rows().is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_enforce_max_dimensions() {
+    assert!(Universe::from_rle("x = 100000, y = 100000\n!").is_err());
+    assert!(Universe::enforce_max_dimensions(0).is_err());
+
+    let mut universe = sized_universe(10, 10);
+    Universe::enforce_max_dimensions(100).unwrap();
+    assert!(Universe::from_rle("x = 11, y = 10\n!").is_err());
+    assert!(universe.resize(11, 10).is_err());
+    assert!(CrittersUniverse::new(12, 10).is_err());
+    universe.set_width(20);
+    assert_eq!(universe.width(), 10);
+    universe.resize(20, 5).unwrap();
+    assert_eq!((universe.width(), universe.height()), (20, 5));
+    Universe::enforce_max_dimensions(1 << 24).unwrap();
+}
+
+#[wasm_bindgen_test]
+pub fn test_highlight_changed_since() {
+    let mut universe = random_universe(16, 12, 8);
+    let before = Universe::load(&universe.save()).unwrap();
+    assert!(universe
+        .highlight_changed_since(&before)
+        .unwrap()
+        .is_empty());
+
+    universe.tick();
+    assert_eq!(
+        universe.highlight_changed_since(&before).unwrap(),
+        universe.changed_cells()
+    );
+    universe.tick();
+    universe.toggle_cell(0, 0);
+    let changed = universe.highlight_changed_since(&before).unwrap();
+    for &index in changed.iter() {
+        assert_ne!(
+            universe.bit_at(index as usize),
+            before.bit_at(index as usize)
+        );
+    }
+    assert!(universe
+        .highlight_changed_since(&sized_universe(12, 16))
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_simulate_to_json() {
+    let json = Universe::simulate_to_json(20, 15, 42, 0.4, 60).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let mut universe = sized_universe(20, 15);
+    universe.randomize(42, 0.4);
+    let mut max_population = universe.population();
+    for _ in 0..60 {
+        universe.tick();
+        max_population = max_population.max(universe.population());
+    }
+    assert_eq!(summary["final_population"], universe.population());
+    assert_eq!(summary["max_population"], max_population);
+    assert_eq!(summary["generation"], 60);
+
+    let settled = Universe::simulate_to_json(8, 8, 0, 0.0, 5).unwrap();
+    let settled: serde_json::Value = serde_json::from_str(&settled).unwrap();
+    assert_eq!(settled["detected_period"], 1);
+    assert_eq!(settled["final_population"], 0);
+    let short = Universe::simulate_to_json(20, 15, 42, 0.4, 0).unwrap();
+    let short: serde_json::Value = serde_json::from_str(&short).unwrap();
+    assert!(short["detected_period"].is_null());
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbors_wrapped_flag() {
+    let mut universe = sized_universe(5, 4);
+    for &(row, col) in [(0, 0), (0, 4), (3, 0), (3, 4), (0, 2), (2, 4)].iter() {
+        assert!(universe.neighbors_wrapped_flag(row, col));
+    }
+    assert!(!universe.neighbors_wrapped_flag(1, 1));
+    assert!(!universe.neighbors_wrapped_flag(2, 3));
+    assert!(!universe.neighbors_wrapped_flag(4, 0));
+
+    universe.set_boundary("dead").unwrap();
+    assert!(!universe.neighbors_wrapped_flag(0, 0));
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_until_population() {
+    let mut gun = sized_universe(64, 48);
+    gun.set_boundary("dead").unwrap();
+    gun.insert_pattern("gosper_glider_gun", 1, 1).unwrap();
+    assert_eq!(gun.run_until_population(36, 10), Some(0));
+
+    // Each glider the gun fires adds five cells.
+    let reached = gun.run_until_population(60, 150).unwrap();
+    assert!(reached > 0 && reached <= 150);
+    assert_eq!(gun.generation(), reached);
+    assert!(gun.population() >= 60);
+
+    let mut blinker = sized_universe(5, 5);
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    assert_eq!(blinker.run_until_population(4, 20), None);
+    assert_eq!(blinker.generation(), 20);
+}
+
+#[wasm_bindgen_test]
+pub fn test_scatter() {
+    let mut universe = sized_universe(200, 150);
+    universe.scatter(5000, 3);
+    assert_eq!(universe.population(), 5000);
+    let mut again = sized_universe(200, 150);
+    again.scatter(5000, 3);
+    assert_eq!(again.as_bool_vec(), universe.as_bool_vec());
+
+    universe.scatter(1000, 4);
+    assert_eq!(universe.population(), 6000);
+
+    let mut small = sized_universe(4, 3);
+    small.set_cells(&[(0, 0)]);
+    small.scatter(100, 5);
+    assert_eq!(small.population(), 12);
+}
+
+#[wasm_bindgen_test]
+pub fn test_as_run_length_rows() {
+    let mut universe = sized_universe(8, 3);
+    universe.set_cells(&[(0, 2), (0, 3), (0, 6), (2, 0), (2, 7)]);
+    let runs = universe.as_run_length_rows();
+    assert_eq!(runs, vec![4, 2, 2, 2, 1, 0, 4, 0, 1, 6, 1]);
+
+    // Decoding gives the cells back.
+    let mut decoded = Vec::new();
+    let mut rest = &runs[..];
+    while let Some((&count, tail)) = rest.split_first() {
+        let (row, tail) = tail.split_at(count as usize);
+        let mut cells = Vec::new();
+        for (i, &run) in row.iter().enumerate() {
+            cells.extend(std::iter::repeat(i as u8 % 2).take(run as usize));
+        }
+        cells.resize(8, 0);
+        decoded.extend(cells);
+        rest = tail;
+    }
+    assert_eq!(decoded, universe.as_bool_vec());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_text() {
+    let mut universe = sized_universe(12, 8);
+    universe.insert_text("I", 1, 2).unwrap();
+    for row in 1..6 {
+        assert!(universe.bit_at(universe.get_index(row, 3)));
+    }
+    for &col in [2, 4].iter() {
+        assert!(universe.bit_at(universe.get_index(1, col)));
+        assert!(universe.bit_at(universe.get_index(5, col)));
+    }
+    assert_eq!(universe.population(), 9);
+
+    let mut clipped = sized_universe(6, 4);
+    clipped.insert_text("hi\nhi", 0, 2).unwrap();
+    assert_eq!(
+        clipped.to_ascii_grid('#', '.', false),
+        "..#.#.\n..#.#.\n..###.\n..#.#."
+    );
+}